@@ -3,6 +3,7 @@ use iced::{
     widget::{Button, Column, Row},
 };
 
+use crate::model::campaign::Campaign;
 use crate::ui::screens::cipher::{self, CipherState};
 use crate::ui::screens::fence::{self, FenceState};
 
@@ -44,7 +45,7 @@ impl App {
         (
             Self {
                 screen: Screen::Cipher,
-                fence: FenceState::default(),
+                fence: FenceState::from_campaign(Campaign::load()),
                 cipher: CipherState::default(),
             },
             Task::none(),
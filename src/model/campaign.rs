@@ -0,0 +1,253 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::catalog::{Catalog, LootBag};
+use super::fence::generate::NameLists;
+use super::fence::{Fence, RoundingMode};
+use super::ledger;
+use super::money::Denominations;
+use super::party::Party;
+use super::region::Regions;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Campaign {
+    pub fences: Vec<Fence>,
+    #[serde(default)]
+    pub catalog: Catalog,
+    #[serde(default)]
+    pub loot: LootBag,
+    #[serde(default)]
+    pub denominations: Denominations,
+    #[serde(default)]
+    pub rounding: RoundingMode,
+    #[serde(default)]
+    pub calendar: Calendar,
+    #[serde(default)]
+    pub regions: Regions,
+    #[serde(default)]
+    pub party: Party,
+    /// What the fence generator names fences from.
+    #[serde(default)]
+    pub names: NameLists,
+}
+
+/// Where the campaign is in game time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Calendar {
+    pub day: u32,
+    pub session: u32,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self { day: 1, session: 1 }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum CampaignError {
+    NoDataDir,
+    NotFound(PathBuf),
+    Io(String),
+    Malformed(String),
+}
+
+impl fmt::Display for CampaignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CampaignError::NoDataDir => write!(f, "Could not find a user data directory"),
+            CampaignError::NotFound(path) => {
+                write!(f, "No campaign file found at {}", path.display())
+            }
+            CampaignError::Io(err) => write!(f, "Could not access campaign file: {err}"),
+            CampaignError::Malformed(err) => write!(f, "Campaign file is malformed: {err}"),
+        }
+    }
+}
+
+impl Campaign {
+    const APP_DIR: &str = "underworld";
+    const FILE_NAME: &str = "campaign.json";
+    const LEDGER_FILE_NAME: &str = "ledger.csv";
+
+    pub fn default_path() -> Result<PathBuf, CampaignError> {
+        dirs::data_dir()
+            .map(|dir| dir.join(Self::APP_DIR).join(Self::FILE_NAME))
+            .ok_or(CampaignError::NoDataDir)
+    }
+
+    pub fn load() -> Result<Self, CampaignError> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    pub fn save(&self) -> Result<(), CampaignError> {
+        self.save_to(&Self::default_path()?)
+    }
+
+    /// Moves a campaign file that couldn't be loaded out of the way, returning where it went.
+    pub fn back_up() -> Result<PathBuf, CampaignError> {
+        Self::back_up_from(&Self::default_path()?)
+    }
+
+    pub fn back_up_from(path: &Path) -> Result<PathBuf, CampaignError> {
+        let backup = path.with_extension("json.bak");
+        fs::rename(path, &backup).map_err(|err| CampaignError::Io(err.to_string()))?;
+        Ok(backup)
+    }
+
+    /// Writes every fence's ledger next to the campaign file, returning where it went.
+    pub fn export_ledger(&self) -> Result<PathBuf, CampaignError> {
+        let path = Self::default_path()?.with_file_name(Self::LEDGER_FILE_NAME);
+        self.export_ledger_to(&path)?;
+        Ok(path)
+    }
+
+    pub fn export_ledger_to(&self, path: &Path) -> Result<(), CampaignError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| CampaignError::Io(err.to_string()))?;
+        }
+
+        let rows = self.fences.iter().flat_map(|fence| {
+            fence
+                .ledger
+                .transactions
+                .iter()
+                .map(|transaction| (fence.name.as_str(), transaction))
+        });
+        fs::write(path, ledger::to_csv(rows)).map_err(|err| CampaignError::Io(err.to_string()))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, CampaignError> {
+        let contents = fs::read_to_string(path).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => CampaignError::NotFound(path.to_path_buf()),
+            _ => CampaignError::Io(err.to_string()),
+        })?;
+
+        serde_json::from_str(&contents).map_err(|err| CampaignError::Malformed(err.to_string()))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), CampaignError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| CampaignError::Io(err.to_string()))?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|err| CampaignError::Malformed(err.to_string()))?;
+
+        fs::write(path, contents).map_err(|err| CampaignError::Io(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fence::Side;
+    use crate::model::ledger::{TradedItem, Transaction};
+    use crate::model::money::Money;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("underworld-test-{}", std::process::id()))
+            .join(name)
+    }
+
+    #[test]
+    fn campaign_round_trips_through_disk() {
+        let path = temp_path("round_trip.json");
+        let campaign = Campaign {
+            fences: vec![Fence::default()],
+            ..Campaign::default()
+        };
+
+        campaign.save_to(&path).unwrap();
+        let loaded = Campaign::load_from(&path).unwrap();
+
+        assert_eq!(loaded.fences.len(), 1);
+        assert_eq!(loaded.fences[0].name, "Merchant");
+        assert_eq!(loaded.fences[0].avg_markup.0, 1100);
+        assert_eq!(loaded.catalog.items, campaign.catalog.items);
+    }
+
+    #[test]
+    fn ledger_is_saved_and_exported() {
+        let path = temp_path("ledger.json");
+        let mut fence = Fence::default();
+        fence.ledger.record(Transaction {
+            day: 4,
+            session: 2,
+            character: "Vex".into(),
+            side: Side::Buy,
+            items: TradedItem::parse_list("Ruby"),
+            quoted: Money(30_000),
+            price: Money(32_500),
+        });
+        let campaign = Campaign {
+            fences: vec![fence],
+            calendar: Calendar { day: 4, session: 2 },
+            ..Campaign::default()
+        };
+
+        campaign.save_to(&path).unwrap();
+        let loaded = Campaign::load_from(&path).unwrap();
+        assert_eq!(
+            loaded.fences[0].ledger.transactions,
+            campaign.fences[0].ledger.transactions
+        );
+        assert_eq!(loaded.calendar, campaign.calendar);
+
+        let csv_path = temp_path("ledger.csv");
+        loaded.export_ledger_to(&csv_path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&csv_path).unwrap(),
+            "fence,day,session,character,side,items,quoted,price\n\
+             Merchant,4,2,Vex,buy,Ruby,30000,32500\n"
+        );
+    }
+
+    #[test]
+    fn campaign_without_catalog_gets_default_catalog() {
+        let campaign: Campaign = serde_json::from_str("{ \"fences\": [] }").unwrap();
+        assert_eq!(campaign.catalog.items, Catalog::default().items);
+        assert!(campaign.loot.is_empty());
+    }
+
+    #[test]
+    fn missing_campaign_file_fails() {
+        let path = temp_path("missing.json");
+        let err = Campaign::load_from(&path).unwrap_err();
+        assert_eq!(err, CampaignError::NotFound(path));
+    }
+
+    #[test]
+    fn malformed_campaign_file_fails() {
+        let path = temp_path("malformed.json");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{ \"fences\": [ { \"name\": 3 } ] }").unwrap();
+
+        let err = Campaign::load_from(&path).unwrap_err();
+        assert!(matches!(err, CampaignError::Malformed(_)));
+    }
+
+    #[test]
+    fn malformed_campaign_file_survives_a_save() {
+        let path = temp_path("kept.json");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let contents = "{ \"fences\": [ { \"name\": 3 } ] }";
+        fs::write(&path, contents).unwrap();
+        assert!(matches!(
+            Campaign::load_from(&path),
+            Err(CampaignError::Malformed(_))
+        ));
+
+        let backup = Campaign::back_up_from(&path).unwrap();
+        Campaign::default().save_to(&path).unwrap();
+
+        assert_eq!(backup, path.with_extension("json.bak"));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), contents);
+        assert!(Campaign::load_from(&path).is_ok());
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::fence::PriceError;
+use super::heat::Heat;
+use super::money::Money;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Category {
+    Art,
+    Jewelry,
+    Gems,
+    Weapons,
+    Armor,
+    Magic,
+    TradeGoods,
+    Curios,
+}
+
+impl Category {
+    pub const ALL: [Category; 8] = [
+        Category::Art,
+        Category::Jewelry,
+        Category::Gems,
+        Category::Weapons,
+        Category::Armor,
+        Category::Magic,
+        Category::TradeGoods,
+        Category::Curios,
+    ];
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Category::Art => "Art",
+            Category::Jewelry => "Jewelry",
+            Category::Gems => "Gems",
+            Category::Weapons => "Weapons",
+            Category::Armor => "Armor",
+            Category::Magic => "Magic",
+            Category::TradeGoods => "Trade goods",
+            Category::Curios => "Curios",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    VeryRare,
+    Legendary,
+}
+
+impl Rarity {
+    pub const ALL: [Rarity; 5] = [
+        Rarity::Common,
+        Rarity::Uncommon,
+        Rarity::Rare,
+        Rarity::VeryRare,
+        Rarity::Legendary,
+    ];
+}
+
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Rarity::Common => "Common",
+            Rarity::Uncommon => "Uncommon",
+            Rarity::Rare => "Rare",
+            Rarity::VeryRare => "Very rare",
+            Rarity::Legendary => "Legendary",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub category: Category,
+    pub base_value: Money,
+    pub weight: f32,
+    pub rarity: Rarity,
+    /// How hot the item is when freshly stolen.
+    #[serde(default)]
+    pub heat: Heat,
+}
+
+impl Item {
+    pub fn new(
+        name: &str,
+        category: Category,
+        base_value: Money,
+        weight: f32,
+        rarity: Rarity,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            category,
+            base_value,
+            weight,
+            rarity,
+            heat: Heat::default(),
+        }
+    }
+
+    /// Reads a weight in pounds, blank for none. Rejects anything that isn't a finite,
+    /// non-negative number, since those can't be saved.
+    pub fn parse_weight(input: &str) -> Option<f32> {
+        match input.trim() {
+            "" => Some(0.0),
+            weight => weight
+                .parse::<f32>()
+                .ok()
+                .filter(|weight| weight.is_finite() && *weight >= 0.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    pub items: Vec<Item>,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self {
+            items: vec![
+                Item::new(
+                    "Silver candlestick",
+                    Category::Art,
+                    Money(5_000),
+                    2.0,
+                    Rarity::Common,
+                ),
+                Item::new(
+                    "Gold ring",
+                    Category::Jewelry,
+                    Money(12_000),
+                    0.1,
+                    Rarity::Common,
+                ),
+                Item {
+                    heat: Heat(40),
+                    ..Item::new("Ruby", Category::Gems, Money(50_000), 0.0, Rarity::Uncommon)
+                },
+                Item::new(
+                    "Fine longsword",
+                    Category::Weapons,
+                    Money(15_000),
+                    3.0,
+                    Rarity::Uncommon,
+                ),
+                Item::new(
+                    "Bolt of silk",
+                    Category::TradeGoods,
+                    Money(8_000),
+                    5.0,
+                    Rarity::Common,
+                ),
+            ],
+        }
+    }
+}
+
+impl Catalog {
+    /// The category of the catalog item called `name`, ignoring case.
+    pub fn category_of(&self, name: &str) -> Option<Category> {
+        let name = name.trim();
+        self.items
+            .iter()
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+            .map(|item| item.category)
+    }
+
+    /// Items whose name or category contains `query`, ignoring case, with their catalog index.
+    pub fn search<'a>(&'a self, query: &str) -> impl Iterator<Item = (usize, &'a Item)> {
+        let query = query.trim().to_lowercase();
+        self.items.iter().enumerate().filter(move |(_, item)| {
+            item.name.to_lowercase().contains(&query)
+                || item.category.to_string().to_lowercase().contains(&query)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootEntry {
+    pub item: Item,
+    pub quantity: u32,
+    /// The in-game day the item was taken, which its heat decays from.
+    #[serde(default)]
+    pub acquired_on: u32,
+}
+
+impl LootEntry {
+    pub fn heat_on(&self, day: u32) -> Heat {
+        self.item.heat.decayed(day.saturating_sub(self.acquired_on))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LootBag {
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootBag {
+    /// Adds one `item` taken on `day`. Items only stack with ones taken the same day, so
+    /// each stack cools off on its own.
+    pub fn add(&mut self, item: &Item, day: u32) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.item == *item && entry.acquired_on == day)
+        {
+            Some(entry) => entry.quantity += 1,
+            None => self.entries.push(LootEntry {
+                item: item.clone(),
+                quantity: 1,
+                acquired_on: day,
+            }),
+        }
+    }
+
+    /// Takes one of the entry at `index` out of the bag, dropping the entry once it runs out.
+    pub fn remove_one(&mut self, index: usize) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.quantity = entry.quantity.saturating_sub(1);
+            if entry.quantity == 0 {
+                self.entries.remove(index);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn total_base_value(&self) -> Result<Money, PriceError> {
+        self.entries
+            .iter()
+            .try_fold(0u64, |total, entry| {
+                entry
+                    .item
+                    .base_value
+                    .0
+                    .checked_mul(u64::from(entry.quantity))
+                    .and_then(|value| total.checked_add(value))
+            })
+            .map(Money)
+            .ok_or(PriceError::Overflow)
+    }
+
+    pub fn total_weight(&self) -> f32 {
+        self.entries
+            .iter()
+            .map(|entry| entry.item.weight * entry.quantity as f32)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_matches_name_and_category_ignoring_case() {
+        let catalog = Catalog::default();
+
+        let by_name: Vec<_> = catalog.search("CANDLE").map(|(i, _)| i).collect();
+        assert_eq!(by_name, vec![0]);
+
+        let by_category: Vec<_> = catalog.search("gems").map(|(_, item)| &item.name).collect();
+        assert_eq!(by_category, vec!["Ruby"]);
+
+        assert_eq!(catalog.search("").count(), catalog.items.len());
+    }
+
+    #[test]
+    fn weights_must_be_finite_and_not_negative() {
+        assert_eq!(Item::parse_weight(" 2.5 "), Some(2.5));
+        assert_eq!(Item::parse_weight(""), Some(0.0));
+        for weight in ["NaN", "inf", "-infinity", "-1", "heavy"] {
+            assert_eq!(Item::parse_weight(weight), None, "{weight}");
+        }
+    }
+
+    #[test]
+    fn adding_same_item_stacks_quantity() {
+        let catalog = Catalog::default();
+        let mut bag = LootBag::default();
+
+        bag.add(&catalog.items[0], 1);
+        bag.add(&catalog.items[0], 1);
+        bag.add(&catalog.items[1], 1);
+
+        assert_eq!(bag.entries.len(), 2);
+        assert_eq!(bag.entries[0].quantity, 2);
+        assert_eq!(bag.total_base_value(), Ok(Money(22_000)));
+
+        bag.entries[0].quantity = u32::MAX;
+        bag.entries[0].item.base_value = Money(u64::MAX / 2);
+        assert_eq!(bag.total_base_value(), Err(PriceError::Overflow));
+    }
+
+    #[test]
+    fn loot_taken_on_different_days_cools_separately() {
+        let catalog = Catalog::default();
+        let mut bag = LootBag::default();
+
+        bag.add(&catalog.items[2], 1);
+        bag.add(&catalog.items[2], 3);
+
+        assert_eq!(bag.entries.len(), 2);
+        assert_eq!(bag.entries[0].heat_on(4), Heat(25));
+        assert_eq!(bag.entries[1].heat_on(4), Heat(35));
+    }
+
+    #[test]
+    fn removing_last_unit_drops_entry() {
+        let catalog = Catalog::default();
+        let mut bag = LootBag::default();
+
+        bag.add(&catalog.items[2], 1);
+        bag.remove_one(0);
+
+        assert!(bag.is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+
+use super::cipher_traits::CipherTraits;
+
+#[derive(Debug, PartialEq)]
+pub enum AffineError {
+    /// `a` shares a factor with 26, so two letters would encrypt the same way.
+    NotCoprime { a: u8 },
+}
+
+impl fmt::Display for AffineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AffineError::NotCoprime { a } => {
+                write!(
+                    f,
+                    "{a} shares a factor with 26, pick 1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23 or 25"
+                )
+            }
+        }
+    }
+}
+
+/// Encrypts each letter x as (a * x + b) mod 26.
+#[derive(Debug)]
+pub struct Affine {
+    pub a: u8,
+    pub b: u8,
+    a_inverse: u8,
+}
+
+impl Affine {
+    pub fn new(a: i16, b: i16) -> Result<Self, AffineError> {
+        let a = a.rem_euclid(26) as u8;
+        let b = b.rem_euclid(26) as u8;
+        let a_inverse = (1..26u8)
+            .find(|inverse| (u16::from(a) * u16::from(*inverse)) % 26 == 1)
+            .ok_or(AffineError::NotCoprime { a })?;
+
+        Ok(Self { a, b, a_inverse })
+    }
+
+    fn map_char(c: char, f: impl Fn(u16) -> u16) -> char {
+        let base = if c.is_ascii_uppercase() {
+            b'A'
+        } else if c.is_ascii_lowercase() {
+            b'a'
+        } else {
+            return c;
+        };
+        (base + (f(u16::from(c as u8 - base)) % 26) as u8) as char
+    }
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self {
+            a: 5,
+            b: 8,
+            a_inverse: 21,
+        }
+    }
+}
+
+impl CipherTraits for Affine {
+    fn encrypt(&self, plain_text: &str) -> String {
+        let (a, b) = (u16::from(self.a), u16::from(self.b));
+        plain_text
+            .chars()
+            .map(|c| Affine::map_char(c, |x| a * x + b))
+            .collect()
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        let (inverse, b) = (u16::from(self.a_inverse), u16::from(self.b));
+        cipher_text
+            .chars()
+            .map(|c| Affine::map_char(c, |y| inverse * (y + 26 - b)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_not_coprime_to_26_fails() {
+        assert_eq!(
+            Affine::new(13, 1).unwrap_err(),
+            AffineError::NotCoprime { a: 13 }
+        );
+        assert_eq!(
+            Affine::new(4, 1).unwrap_err(),
+            AffineError::NotCoprime { a: 4 }
+        );
+        assert_eq!(
+            Affine::new(26, 1).unwrap_err(),
+            AffineError::NotCoprime { a: 0 }
+        );
+    }
+
+    #[test]
+    fn affine_encrypts_correctly() {
+        let cipher = Affine::new(5, 8).unwrap();
+        assert_eq!(cipher.encrypt("Affine Cipher"), "Ihhwvc Swfrcp");
+    }
+
+    #[test]
+    fn affine_encrypt_decrypt() {
+        for (a, b) in [(1, 0), (3, 7), (25, 25), (-5, 30)] {
+            let cipher = Affine::new(a, b).unwrap();
+            let plain = "Attack at dawn, Zed!";
+            assert_eq!(cipher.decrypt(&cipher.encrypt(plain)), plain);
+        }
+    }
+}
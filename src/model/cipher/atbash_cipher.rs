@@ -0,0 +1,47 @@
+use super::cipher_traits::CipherTraits;
+
+/// Mirrors the alphabet, A for Z, B for Y and so on. Encrypting and decrypting are the same.
+#[derive(Default, Debug)]
+pub struct Atbash;
+
+impl Atbash {
+    fn mirror_char(c: char) -> char {
+        if c.is_ascii_uppercase() {
+            (b'Z' - (c as u8 - b'A')) as char
+        } else if c.is_ascii_lowercase() {
+            (b'z' - (c as u8 - b'a')) as char
+        } else {
+            c
+        }
+    }
+}
+
+impl CipherTraits for Atbash {
+    fn encrypt(&self, plain_text: &str) -> String {
+        plain_text.chars().map(Atbash::mirror_char).collect()
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        self.encrypt(cipher_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atbash_encrypts_correctly() {
+        assert_eq!(Atbash.encrypt("Wizard of Oz!"), "Draziw lu La!");
+        assert_eq!(
+            Atbash.encrypt("ABCDEFGHIJKLMNOPQRSTUVWXYZ"),
+            "ZYXWVUTSRQPONMLKJIHGFEDCBA"
+        );
+    }
+
+    #[test]
+    fn atbash_encrypt_decrypt() {
+        let plain = "Attack at dawn!";
+        assert_eq!(Atbash.decrypt(&Atbash.encrypt(plain)), plain);
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+
+use super::cipher_traits::CipherTraits;
+use super::transposition::{transpose, untranspose};
+
+#[derive(Debug, PartialEq)]
+pub enum ColumnarError {
+    EmptyKey,
+}
+
+impl fmt::Display for ColumnarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnarError::EmptyKey => write!(f, "The key needs at least one letter or digit"),
+        }
+    }
+}
+
+/// Writes the text in rows as wide as the key, then reads the columns off in the key's
+/// alphabetical order, repeated letters left to right. The last row is left short rather
+/// than padded, so no filler ever ends up in the decrypted text.
+#[derive(Debug)]
+pub struct Columnar {
+    pub key: String,
+    /// Columns in the order they are read.
+    columns: Vec<usize>,
+}
+
+impl Columnar {
+    pub fn new(key: impl Into<String>) -> Result<Self, ColumnarError> {
+        let key = key.into();
+        let letters: Vec<char> = key
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if letters.is_empty() {
+            return Err(ColumnarError::EmptyKey);
+        }
+
+        let mut columns: Vec<usize> = (0..letters.len()).collect();
+        columns.sort_by_key(|&column| letters[column]);
+        Ok(Self { key, columns })
+    }
+
+    fn order(&self, length: usize) -> Vec<usize> {
+        let width = self.columns.len();
+        self.columns
+            .iter()
+            .flat_map(|&column| (column..length).step_by(width))
+            .collect()
+    }
+}
+
+impl CipherTraits for Columnar {
+    fn encrypt(&self, plain_text: &str) -> String {
+        transpose(plain_text, &self.order(plain_text.chars().count()))
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        untranspose(cipher_text, &self.order(cipher_text.chars().count()))
+    }
+}
+
+/// Two columnar transpositions in a row, the second over the output of the first.
+#[derive(Debug)]
+pub struct DoubleColumnar {
+    pub first: Columnar,
+    pub second: Columnar,
+}
+
+impl DoubleColumnar {
+    pub fn new(first: impl Into<String>, second: impl Into<String>) -> Result<Self, ColumnarError> {
+        Ok(Self {
+            first: Columnar::new(first)?,
+            second: Columnar::new(second)?,
+        })
+    }
+}
+
+impl CipherTraits for DoubleColumnar {
+    fn encrypt(&self, plain_text: &str) -> String {
+        self.second.encrypt(&self.first.encrypt(plain_text))
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        self.first.decrypt(&self.second.decrypt(cipher_text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_key_fails() {
+        assert_eq!(Columnar::new("--").unwrap_err(), ColumnarError::EmptyKey);
+        assert_eq!(
+            DoubleColumnar::new("KEY", "").unwrap_err(),
+            ColumnarError::EmptyKey
+        );
+    }
+
+    #[test]
+    fn columnar_encrypts_correctly() {
+        let cipher = Columnar::new("ZEBRAS").unwrap();
+        assert_eq!(
+            cipher.encrypt("WEAREDISCOVEREDFLEEATONCE"),
+            "EVLNACDTESEAROFODEECWIREE"
+        );
+    }
+
+    #[test]
+    fn repeated_key_letters_read_left_to_right() {
+        let cipher = Columnar::new("BAB").unwrap();
+        assert_eq!(cipher.encrypt("ABCDEF"), "BEADCF");
+    }
+
+    #[test]
+    fn columnar_encrypt_decrypt() {
+        let plain = "Attack at dawn! Ünïcode too.";
+        for key in ["A", "ZEBRAS", "key", "LONGERTHANTHEMESSAGEITSELFBYFAR"] {
+            let cipher = Columnar::new(key).unwrap();
+            for length in 0..=plain.chars().count() {
+                let text: String = plain.chars().take(length).collect();
+                assert_eq!(cipher.decrypt(&cipher.encrypt(&text)), text);
+            }
+        }
+    }
+
+    #[test]
+    fn double_columnar_encrypt_decrypt() {
+        let cipher = DoubleColumnar::new("ZEBRA", "STRIPE").unwrap();
+        let plain = "Attack at dawn, hold the bridge!";
+        let encrypted = cipher.encrypt(plain);
+
+        assert_ne!(encrypted, cipher.first.encrypt(plain));
+        assert_eq!(cipher.decrypt(&encrypted), plain);
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum SquareError {
+    /// Fillers must be a letter other than J, which the square merges into I.
+    InvalidFiller(char),
+}
+
+impl fmt::Display for SquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SquareError::InvalidFiller(filler) => {
+                write!(f, "{filler} can't be a filler, pick a letter other than J")
+            }
+        }
+    }
+}
+
+/// A 5x5 square of the alphabet with I and J sharing a cell, mixed by a keyword: its letters
+/// first, repeats dropped, then the rest of the alphabet in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySquare {
+    pub rows: [[char; 5]; 5],
+}
+
+impl KeySquare {
+    pub fn new(keyword: &str) -> Self {
+        let mut letters: Vec<char> = Vec::with_capacity(25);
+        for letter in normalize(keyword).chain(normalize("ABCDEFGHIKLMNOPQRSTUVWXYZ")) {
+            if !letters.contains(&letter) {
+                letters.push(letter);
+            }
+        }
+        let mut rows = [[' '; 5]; 5];
+        for (i, letter) in letters.into_iter().enumerate() {
+            rows[i / 5][i % 5] = letter;
+        }
+        Self { rows }
+    }
+
+    /// The square with no keyword, A to Z.
+    pub fn plain() -> Self {
+        Self::new("")
+    }
+
+    /// Row and column of `letter`, which must already be normalized.
+    pub fn position(&self, letter: char) -> (usize, usize) {
+        (0..25)
+            .map(|i| (i / 5, i % 5))
+            .find(|&(row, column)| self.rows[row][column] == letter)
+            .unwrap_or_default()
+    }
+
+    pub fn at(&self, row: usize, column: usize) -> char {
+        self.rows[row % 5][column % 5]
+    }
+}
+
+/// The letters of `text` as the squares hold them: upper case, J as I, everything else dropped.
+pub fn normalize(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| match c.to_ascii_uppercase() {
+            'J' => 'I',
+            c => c,
+        })
+}
+
+/// Checks a filler letter, returning it in upper case.
+pub fn filler(filler: char) -> Result<char, SquareError> {
+    match filler.to_ascii_uppercase() {
+        'J' => Err(SquareError::InvalidFiller(filler)),
+        c if c.is_ascii_uppercase() => Ok(c),
+        _ => Err(SquareError::InvalidFiller(filler)),
+    }
+}
+
+/// Splits `text` into pairs of letters, padding an odd one out with `filler`. With
+/// `split_doubles`, a pair of the same letter gets the filler slipped between them;
+/// doubled fillers get a Q instead, or an X if Q is the filler.
+pub fn digraphs(text: &str, filler: char, split_doubles: bool) -> Vec<(char, char)> {
+    let letters: Vec<char> = normalize(text).collect();
+    let pad = |letter: char| match (letter == filler, filler) {
+        (false, _) => filler,
+        (true, 'Q') => 'X',
+        (true, _) => 'Q',
+    };
+    let mut pairs = Vec::with_capacity(letters.len() / 2 + 1);
+    let mut i = 0;
+    while i < letters.len() {
+        let first = letters[i];
+        match letters.get(i + 1) {
+            Some(&second) if !split_doubles || second != first => {
+                pairs.push((first, second));
+                i += 2;
+            }
+            _ => {
+                pairs.push((first, pad(first)));
+                i += 1;
+            }
+        }
+    }
+    pairs
+}
+
+pub fn join(pairs: impl IntoIterator<Item = (char, char)>) -> String {
+    pairs.into_iter().flat_map(|(a, b)| [a, b]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyword_mixes_the_square() {
+        let square = KeySquare::new("Playfair example");
+        assert_eq!(
+            square.rows,
+            [
+                ['P', 'L', 'A', 'Y', 'F'],
+                ['I', 'R', 'E', 'X', 'M'],
+                ['B', 'C', 'D', 'G', 'H'],
+                ['K', 'N', 'O', 'Q', 'S'],
+                ['T', 'U', 'V', 'W', 'Z'],
+            ]
+        );
+        assert_eq!(square.position('I'), (1, 0));
+        assert_eq!(KeySquare::plain().rows[1], ['F', 'G', 'H', 'I', 'K']);
+    }
+
+    #[test]
+    fn digraphs_split_doubles_and_pad() {
+        assert_eq!(join(digraphs("Hide the gold", 'X', true)), "HIDETHEGOLDX");
+        assert_eq!(join(digraphs("tree", 'X', true)), "TREXEX");
+        assert_eq!(join(digraphs("xx", 'X', true)), "XQXQ");
+        assert_eq!(join(digraphs("tree", 'X', false)), "TREE");
+        assert_eq!(join(digraphs("Jam", 'Z', false)), "IAMZ");
+    }
+
+    #[test]
+    fn fillers_must_be_letters_other_than_j() {
+        assert_eq!(filler('q'), Ok('Q'));
+        assert_eq!(filler('j'), Err(SquareError::InvalidFiller('j')));
+        assert_eq!(filler('5'), Err(SquareError::InvalidFiller('5')));
+    }
+}
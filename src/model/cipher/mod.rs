@@ -1,3 +1,13 @@
+pub mod affine_cipher;
+pub mod atbash_cipher;
 pub mod caesar_cipher;
 pub mod cipher_traits;
+pub mod columnar_cipher;
+pub mod key_square;
+pub mod playfair_cipher;
+pub mod rail_fence_cipher;
+pub mod rotor_machine;
+pub mod square_cipher;
+pub mod substitution_cipher;
+pub mod transposition;
 pub mod vigenere_cipher;
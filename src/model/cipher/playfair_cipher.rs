@@ -0,0 +1,87 @@
+use super::cipher_traits::CipherTraits;
+use super::key_square::{self, KeySquare, SquareError, digraphs, join};
+
+/// Swaps pairs of letters by where they sit in a keyed square. Drops everything but letters,
+/// reads J as I and slips the filler between doubled letters, so decrypting gives back the
+/// letters as they were paired up rather than the original text.
+#[derive(Debug)]
+pub struct Playfair {
+    pub square: KeySquare,
+    pub filler: char,
+}
+
+impl Playfair {
+    pub fn new(keyword: &str, filler: char) -> Result<Self, SquareError> {
+        Ok(Self {
+            square: KeySquare::new(keyword),
+            filler: key_square::filler(filler)?,
+        })
+    }
+
+    fn transform(&self, text: &str, step: usize) -> String {
+        let square = &self.square;
+        join(digraphs(text, self.filler, true).into_iter().map(|(a, b)| {
+            let (row_a, column_a) = square.position(a);
+            let (row_b, column_b) = square.position(b);
+            if row_a == row_b {
+                (
+                    square.at(row_a, column_a + step),
+                    square.at(row_b, column_b + step),
+                )
+            } else if column_a == column_b {
+                (
+                    square.at(row_a + step, column_a),
+                    square.at(row_b + step, column_b),
+                )
+            } else {
+                (square.at(row_a, column_b), square.at(row_b, column_a))
+            }
+        }))
+    }
+}
+
+impl CipherTraits for Playfair {
+    fn encrypt(&self, plain_text: &str) -> String {
+        self.transform(plain_text, 1)
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        self.transform(cipher_text, 4)
+    }
+
+    /// Pairs the guess up the same way encrypting does, fillers and all, before comparing.
+    fn validate_solution(&self, cipher_text: &str, guess: &str) -> bool {
+        self.decrypt(cipher_text) == join(digraphs(guess, self.filler, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_filler_fails() {
+        assert_eq!(
+            Playfair::new("KEY", 'J').unwrap_err(),
+            SquareError::InvalidFiller('J')
+        );
+    }
+
+    #[test]
+    fn playfair_encrypts_correctly() {
+        let cipher = Playfair::new("playfair example", 'x').unwrap();
+        assert_eq!(
+            cipher.encrypt("Hide the gold in the tree stump"),
+            "BMODZBXDNABEKUDMUIXMMOUVIF"
+        );
+    }
+
+    #[test]
+    fn playfair_encrypt_decrypt() {
+        let cipher = Playfair::new("MONARCHY", 'Z').unwrap();
+        let prepared = "INSTRUMENTSZ";
+        assert_eq!(cipher.decrypt(&cipher.encrypt("Instruments")), prepared);
+        assert!(cipher.validate_solution(&cipher.encrypt("balloon"), "Balloon"));
+        assert!(!cipher.validate_solution(&cipher.encrypt("balloon"), "ballroom"));
+    }
+}
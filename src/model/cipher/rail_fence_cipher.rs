@@ -0,0 +1,106 @@
+use std::fmt;
+
+use super::cipher_traits::CipherTraits;
+use super::transposition::{transpose, untranspose};
+
+#[derive(Debug, PartialEq)]
+pub enum RailFenceError {
+    TooFewRails,
+}
+
+impl fmt::Display for RailFenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RailFenceError::TooFewRails => write!(f, "A rail fence needs at least 2 rails"),
+        }
+    }
+}
+
+/// Writes the text in a zigzag down and up `rails` rails, then reads it off rail by rail.
+/// `offset` starts the zigzag that many steps in. Spaces and punctuation are moved too.
+#[derive(Debug)]
+pub struct RailFence {
+    pub rails: usize,
+    pub offset: usize,
+}
+
+impl RailFence {
+    pub fn new(rails: usize, offset: usize) -> Result<Self, RailFenceError> {
+        if rails < 2 {
+            return Err(RailFenceError::TooFewRails);
+        }
+        Ok(Self {
+            rails,
+            offset: offset % (2 * (rails - 1)),
+        })
+    }
+
+    fn rail_of(&self, position: usize) -> usize {
+        let period = 2 * (self.rails - 1);
+        let step = (position + self.offset) % period;
+        step.min(period - step)
+    }
+
+    fn order(&self, length: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..length).collect();
+        order.sort_by_key(|&position| self.rail_of(position));
+        order
+    }
+}
+
+impl Default for RailFence {
+    fn default() -> Self {
+        Self {
+            rails: 3,
+            offset: 0,
+        }
+    }
+}
+
+impl CipherTraits for RailFence {
+    fn encrypt(&self, plain_text: &str) -> String {
+        transpose(plain_text, &self.order(plain_text.chars().count()))
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        untranspose(cipher_text, &self.order(cipher_text.chars().count()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn too_few_rails_fails() {
+        assert_eq!(
+            RailFence::new(1, 0).unwrap_err(),
+            RailFenceError::TooFewRails
+        );
+    }
+
+    #[test]
+    fn rail_fence_encrypts_correctly() {
+        let plain = "WEAREDISCOVEREDFLEEATONCE";
+        let cipher = RailFence::new(3, 0).unwrap();
+        assert_eq!(cipher.encrypt(plain), "WECRLTEERDSOEEFEAOCAIVDEN");
+
+        // One step in, A starts on the middle rail heading down, so D is first to reach the top.
+        let offset = RailFence::new(3, 1).unwrap();
+        assert_eq!(offset.encrypt("ABCDEF"), "DACEBF");
+    }
+
+    #[test]
+    fn rail_fence_encrypt_decrypt() {
+        let plain = "Attack at dawn! Ünïcode too.";
+        for rails in 2..8 {
+            for offset in 0..12 {
+                let cipher = RailFence::new(rails, offset).unwrap();
+                for length in 0..=plain.chars().count() {
+                    let text: String = plain.chars().take(length).collect();
+                    assert_eq!(cipher.decrypt(&cipher.encrypt(&text)), text);
+                }
+            }
+        }
+    }
+}
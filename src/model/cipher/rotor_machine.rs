@@ -0,0 +1,459 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::cipher_traits::CipherTraits;
+
+#[derive(Debug, PartialEq)]
+pub enum RotorError {
+    NoRotors,
+    /// The wiring doesn't use each letter exactly once.
+    NotPermutation(String),
+    /// A reflector must swap letters in pairs, none wired to itself.
+    NotPaired(String),
+    InvalidPlug(String),
+    PlugReused(char),
+    UnknownRotor(String),
+    UnknownReflector(String),
+    InvalidLetter(char),
+    /// Ring settings and positions need one letter per rotor.
+    SettingLength {
+        rotors: usize,
+    },
+    TwoReflectors(String),
+}
+
+impl fmt::Display for RotorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RotorError::NoRotors => write!(f, "The machine needs at least one rotor"),
+            RotorError::NotPermutation(wiring) => {
+                write!(f, "{wiring} must use each letter A to Z once")
+            }
+            RotorError::NotPaired(wiring) => {
+                write!(f, "{wiring} must swap letters in pairs")
+            }
+            RotorError::InvalidPlug(plug) => write!(f, "{plug} isn't a pair of letters"),
+            RotorError::PlugReused(letter) => write!(f, "{letter} is plugged twice"),
+            RotorError::UnknownRotor(name) => write!(f, "There's no rotor {name}"),
+            RotorError::UnknownReflector(name) => write!(f, "There's no reflector {name}"),
+            RotorError::InvalidLetter(letter) => write!(f, "{letter} isn't a letter"),
+            RotorError::SettingLength { rotors } => {
+                write!(
+                    f,
+                    "Rings and positions need one letter for each of {rotors} rotors"
+                )
+            }
+            RotorError::TwoReflectors(name) => {
+                write!(f, "{name} is neither a rotor nor the only reflector")
+            }
+        }
+    }
+}
+
+/// Letters as 0 to 25, checking that `wiring` is a permutation of the alphabet.
+fn wiring_table(wiring: &str) -> Result<[u8; 26], RotorError> {
+    let letters = wiring
+        .bytes()
+        .map(|c| c.to_ascii_uppercase().wrapping_sub(b'A'))
+        .collect::<Vec<_>>();
+    let mut seen = [false; 26];
+    let is_permutation = letters.len() == 26
+        && letters
+            .iter()
+            .all(|&c| c < 26 && !std::mem::replace(&mut seen[c as usize], true));
+    match letters.try_into() {
+        Ok(table) if is_permutation => Ok(table),
+        _ => Err(RotorError::NotPermutation(wiring.to_string())),
+    }
+}
+
+fn letter_index(letter: char) -> Result<u8, RotorError> {
+    match letter.is_ascii_alphabetic() {
+        true => Ok(letter.to_ascii_uppercase() as u8 - b'A'),
+        false => Err(RotorError::InvalidLetter(letter)),
+    }
+}
+
+fn letter(index: u8) -> char {
+    (b'A' + index) as char
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rotor {
+    pub name: String,
+    wiring: [u8; 26],
+    inverse: [u8; 26],
+    /// Positions at which the rotor turns its left-hand neighbour.
+    notches: Vec<u8>,
+}
+
+impl Rotor {
+    pub fn new(name: &str, wiring: &str, notches: &str) -> Result<Self, RotorError> {
+        let wiring = wiring_table(wiring)?;
+        let mut inverse = [0; 26];
+        for (from, &to) in wiring.iter().enumerate() {
+            inverse[to as usize] = from as u8;
+        }
+        Ok(Self {
+            name: name.to_string(),
+            wiring,
+            inverse,
+            notches: notches
+                .chars()
+                .map(letter_index)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// The five rotors of the army machine, I to V.
+    pub fn historical() -> Vec<Rotor> {
+        [
+            ("I", "EKMFLGDQVZNTOWYHXUSPAIBRCJ", "Q"),
+            ("II", "AJDKSIRUXBLHWTMCQGZNPYFVOE", "E"),
+            ("III", "BDFHJLCPRTXVZNYEIWGAKMUSQO", "V"),
+            ("IV", "ESOVPZJAYQUIRHXLNFTGKDCMWB", "J"),
+            ("V", "VZBRGITYUPSDNHLXAWMQOFECKJ", "Z"),
+        ]
+        .into_iter()
+        .filter_map(|(name, wiring, notches)| Rotor::new(name, wiring, notches).ok())
+        .collect()
+    }
+
+    pub fn find(name: &str) -> Result<Rotor, RotorError> {
+        Self::historical()
+            .into_iter()
+            .find(|rotor| rotor.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| RotorError::UnknownRotor(name.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reflector {
+    pub name: String,
+    wiring: [u8; 26],
+}
+
+impl Reflector {
+    pub fn new(name: &str, wiring: &str) -> Result<Self, RotorError> {
+        let table = wiring_table(wiring)?;
+        let paired = table
+            .iter()
+            .enumerate()
+            .all(|(from, &to)| from as u8 != to && table[to as usize] == from as u8);
+        match paired {
+            true => Ok(Self {
+                name: name.to_string(),
+                wiring: table,
+            }),
+            false => Err(RotorError::NotPaired(wiring.to_string())),
+        }
+    }
+
+    pub fn historical() -> Vec<Reflector> {
+        [
+            ("A", "EJMZALYXVBWFCRQUONTSPIKHGD"),
+            ("B", "YRUHQSLDPXNGOKMIEBFZCWVJAT"),
+            ("C", "FVPJIAOYEDRZXWGCTKUQSBNMHL"),
+        ]
+        .into_iter()
+        .filter_map(|(name, wiring)| Reflector::new(name, wiring).ok())
+        .collect()
+    }
+
+    pub fn find(name: &str) -> Result<Reflector, RotorError> {
+        Self::historical()
+            .into_iter()
+            .find(|reflector| reflector.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| RotorError::UnknownReflector(name.to_string()))
+    }
+}
+
+/// A rotor in its slot, with its ring setting and the letter showing at the start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotorSetting {
+    pub rotor: Rotor,
+    pub ring: u8,
+    pub position: u8,
+}
+
+impl RotorSetting {
+    pub fn new(rotor: Rotor, ring: char, position: char) -> Result<Self, RotorError> {
+        Ok(Self {
+            rotor,
+            ring: letter_index(ring)?,
+            position: letter_index(position)?,
+        })
+    }
+}
+
+/// One letter through the machine, with the rotor positions it was enciphered at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub input: char,
+    pub output: char,
+    pub positions: String,
+}
+
+/// Rotors are listed left to right, as read through the machine's windows. The right-hand
+/// rotor turns on every letter, and middle rotors double-step as on the original.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotorMachine {
+    pub rotors: Vec<RotorSetting>,
+    pub reflector: Reflector,
+    plugboard: [u8; 26],
+}
+
+impl RotorMachine {
+    /// `plugs` are letter pairs split by spaces, i.e. "AB CD".
+    pub fn new(
+        rotors: Vec<RotorSetting>,
+        reflector: Reflector,
+        plugs: &str,
+    ) -> Result<Self, RotorError> {
+        if rotors.is_empty() {
+            return Err(RotorError::NoRotors);
+        }
+        let mut plugboard: [u8; 26] = std::array::from_fn(|i| i as u8);
+        for plug in plugs.split_whitespace() {
+            let pair = plug
+                .chars()
+                .map(letter_index)
+                .collect::<Result<Vec<_>, _>>();
+            let Ok(Ok([a, b])) = pair.map(<[u8; 2]>::try_from) else {
+                return Err(RotorError::InvalidPlug(plug.to_string()));
+            };
+            if a == b {
+                return Err(RotorError::InvalidPlug(plug.to_string()));
+            }
+            for c in [a, b] {
+                if plugboard[c as usize] != c {
+                    return Err(RotorError::PlugReused(letter(c)));
+                }
+            }
+            plugboard.swap(a as usize, b as usize);
+        }
+        Ok(Self {
+            rotors,
+            reflector,
+            plugboard,
+        })
+    }
+
+    /// The starting positions, as shown in the windows.
+    pub fn positions(&self) -> String {
+        self.rotors.iter().map(|r| letter(r.position)).collect()
+    }
+
+    /// Runs `text` through the machine from its starting positions. Anything but a letter
+    /// passes through without turning the rotors.
+    pub fn steps(&self, text: &str) -> Vec<Step> {
+        let mut positions: Vec<u8> = self.rotors.iter().map(|r| r.position).collect();
+        text.chars()
+            .filter(char::is_ascii_alphabetic)
+            .map(|input| {
+                self.turn(&mut positions);
+                let output =
+                    letter(self.encipher(input.to_ascii_uppercase() as u8 - b'A', &positions));
+                Step {
+                    input,
+                    output: match input.is_ascii_lowercase() {
+                        true => output.to_ascii_lowercase(),
+                        false => output,
+                    },
+                    positions: positions.iter().map(|&p| letter(p)).collect(),
+                }
+            })
+            .collect()
+    }
+
+    fn turn(&self, positions: &mut [u8]) {
+        let last = positions.len() - 1;
+        let at_notch = |slot: usize, positions: &[u8]| {
+            self.rotors[slot].rotor.notches.contains(&positions[slot])
+        };
+        let turns: Vec<bool> = (0..=last)
+            .map(|slot| {
+                slot == last
+                    || at_notch(slot + 1, positions)
+                    || (slot > 0 && at_notch(slot, positions))
+            })
+            .collect();
+        for (position, turns) in positions.iter_mut().zip(turns) {
+            if turns {
+                *position = (*position + 1) % 26;
+            }
+        }
+    }
+
+    fn encipher(&self, c: u8, positions: &[u8]) -> u8 {
+        let through = |c: u8, setting: &RotorSetting, position: u8, table: &[u8; 26]| {
+            let shift = (26 + position - setting.ring) % 26;
+            (table[((c + shift) % 26) as usize] + 26 - shift) % 26
+        };
+        let mut c = self.plugboard[c as usize];
+        for (setting, &position) in self.rotors.iter().zip(positions).rev() {
+            c = through(c, setting, position, &setting.rotor.wiring);
+        }
+        c = self.reflector.wiring[c as usize];
+        for (setting, &position) in self.rotors.iter().zip(positions) {
+            c = through(c, setting, position, &setting.rotor.inverse);
+        }
+        self.plugboard[c as usize]
+    }
+}
+
+/// Reads a key like "I II III B ring=AAA pos=ADU plugs=AB,CD": rotors by name from left to
+/// right and the reflector, B if left out. Rings and positions default to all A.
+impl FromStr for RotorMachine {
+    type Err = RotorError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut rotors = Vec::new();
+        let mut reflector = None;
+        let (mut rings, mut positions, mut plugs) = (None, None, String::new());
+        for word in input.split_whitespace() {
+            match word.split_once('=') {
+                Some(("ring", letters)) => rings = Some(letters),
+                Some(("pos", letters)) => positions = Some(letters),
+                Some(("plugs", pairs)) => plugs = pairs.replace(',', " "),
+                Some(_) => return Err(RotorError::UnknownRotor(word.to_string())),
+                None => match (Rotor::find(word), &reflector) {
+                    (Ok(rotor), _) => rotors.push(rotor),
+                    (Err(_), None) => reflector = Some(Reflector::find(word)?),
+                    (Err(_), Some(_)) => return Err(RotorError::TwoReflectors(word.to_string())),
+                },
+            }
+        }
+        let letters = |setting: Option<&str>| {
+            let letters: Vec<char> = setting.unwrap_or_default().chars().collect();
+            match letters.len() {
+                0 => Ok(vec!['A'; rotors.len()]),
+                n if n == rotors.len() => Ok(letters),
+                _ => Err(RotorError::SettingLength {
+                    rotors: rotors.len(),
+                }),
+            }
+        };
+        let (rings, positions) = (letters(rings)?, letters(positions)?);
+        let settings = rotors
+            .iter()
+            .cloned()
+            .zip(rings.into_iter().zip(positions))
+            .map(|(rotor, (ring, position))| RotorSetting::new(rotor, ring, position))
+            .collect::<Result<_, _>>()?;
+        let reflector = match reflector {
+            Some(reflector) => reflector,
+            None => Reflector::find("B")?,
+        };
+        RotorMachine::new(settings, reflector, &plugs)
+    }
+}
+
+impl CipherTraits for RotorMachine {
+    fn encrypt(&self, plain_text: &str) -> String {
+        let mut steps = self.steps(plain_text).into_iter();
+        plain_text
+            .chars()
+            .map(|c| match c.is_ascii_alphabetic() {
+                true => steps.next().map_or(c, |step| step.output),
+                false => c,
+            })
+            .collect()
+    }
+
+    /// The machine is its own inverse from the same starting positions.
+    fn decrypt(&self, cipher_text: &str) -> String {
+        self.encrypt(cipher_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine(rotors: [&str; 3], rings: &str, positions: &str, plugs: &str) -> RotorMachine {
+        let rotors = rotors
+            .iter()
+            .zip(rings.chars().zip(positions.chars()))
+            .map(|(name, (ring, position))| {
+                RotorSetting::new(Rotor::find(name).unwrap(), ring, position).unwrap()
+            })
+            .collect();
+        RotorMachine::new(rotors, Reflector::find("B").unwrap(), plugs).unwrap()
+    }
+
+    #[test]
+    fn matches_the_original_machine() {
+        let m = machine(["I", "II", "III"], "AAA", "AAA", "");
+        assert_eq!(m.encrypt("AAAAA"), "BDZGO");
+    }
+
+    #[test]
+    fn middle_rotor_double_steps() {
+        let m = machine(["I", "II", "III"], "AAA", "ADU", "");
+        let positions: Vec<_> = m.steps("AAAA").into_iter().map(|s| s.positions).collect();
+        assert_eq!(positions, ["ADV", "AEW", "BFX", "BFY"]);
+    }
+
+    #[test]
+    fn is_self_reciprocal() {
+        let m = machine(
+            ["IV", "II", "V"],
+            "BUL",
+            "RFW",
+            "AV BS CG DL FU HZ IN KM OW RX",
+        );
+        let plain = "Meet the fence at the Drowned Rat, midnight.";
+        let encrypted = m.encrypt(plain);
+        assert_ne!(encrypted, plain);
+        assert_eq!(m.decrypt(&encrypted), plain);
+        assert!(m.validate_solution(&encrypted, plain));
+    }
+
+    #[test]
+    fn rejects_bad_settings() {
+        assert_eq!(
+            Rotor::new("X", "ABC", "").unwrap_err(),
+            RotorError::NotPermutation("ABC".into())
+        );
+        assert_eq!(
+            Reflector::new("X", "ABCDEFGHIJKLMNOPQRSTUVWXYZ").unwrap_err(),
+            RotorError::NotPaired("ABCDEFGHIJKLMNOPQRSTUVWXYZ".into())
+        );
+        let reflector = Reflector::find("B").unwrap();
+        assert_eq!(
+            RotorMachine::new(Vec::new(), reflector.clone(), "").unwrap_err(),
+            RotorError::NoRotors
+        );
+        let rotors = vec![RotorSetting::new(Rotor::find("I").unwrap(), 'A', 'A').unwrap()];
+        assert_eq!(
+            RotorMachine::new(rotors, reflector, "AB BC").unwrap_err(),
+            RotorError::PlugReused('B')
+        );
+    }
+
+    #[test]
+    fn keys_name_each_setting() {
+        let m: RotorMachine = "I II III B ring=AAA pos=AAA".parse().unwrap();
+        assert_eq!(m.encrypt("AAAAA"), "BDZGO");
+
+        // With two rotors, the plug pair AB mustn't be read as rings or positions.
+        let key: RotorMachine = "II V C pos=QE plugs=AB,CD".parse().unwrap();
+        let rotors = vec![
+            RotorSetting::new(Rotor::find("II").unwrap(), 'A', 'Q').unwrap(),
+            RotorSetting::new(Rotor::find("V").unwrap(), 'A', 'E').unwrap(),
+        ];
+        let built = RotorMachine::new(rotors, Reflector::find("C").unwrap(), "AB CD").unwrap();
+        assert_eq!(key, built);
+        assert_eq!(key.positions(), "QE");
+
+        assert_eq!(
+            "I II ring=ABC".parse::<RotorMachine>(),
+            Err(RotorError::SettingLength { rotors: 2 })
+        );
+        assert_eq!(
+            "I II AB".parse::<RotorMachine>(),
+            Err(RotorError::UnknownReflector("AB".into()))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use super::cipher_traits::CipherTraits;
+use super::key_square::{self, KeySquare, SquareError, digraphs, join};
+
+/// Two keyed squares, one above the other. The first letter of a pair is found in the top
+/// square, the second in the bottom, and each swaps for the letter in its own square at the
+/// other's column. Pairs in the same column stay as they are. Encrypting and decrypting are
+/// the same.
+#[derive(Debug)]
+pub struct TwoSquare {
+    pub top: KeySquare,
+    pub bottom: KeySquare,
+    pub filler: char,
+}
+
+impl TwoSquare {
+    pub fn new(top: &str, bottom: &str, filler: char) -> Result<Self, SquareError> {
+        Ok(Self {
+            top: KeySquare::new(top),
+            bottom: KeySquare::new(bottom),
+            filler: key_square::filler(filler)?,
+        })
+    }
+}
+
+impl CipherTraits for TwoSquare {
+    fn encrypt(&self, plain_text: &str) -> String {
+        join(
+            digraphs(plain_text, self.filler, false)
+                .into_iter()
+                .map(|(a, b)| {
+                    let (row_a, column_a) = self.top.position(a);
+                    let (row_b, column_b) = self.bottom.position(b);
+                    match column_a == column_b {
+                        true => (a, b),
+                        false => (
+                            self.top.at(row_a, column_b),
+                            self.bottom.at(row_b, column_a),
+                        ),
+                    }
+                }),
+        )
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        self.encrypt(cipher_text)
+    }
+}
+
+/// Plain squares top left and bottom right, keyed squares top right and bottom left. The
+/// pair is found in the plain squares and read off the keyed ones at the corners of the
+/// rectangle between them.
+#[derive(Debug)]
+pub struct FourSquare {
+    pub top_right: KeySquare,
+    pub bottom_left: KeySquare,
+    pub filler: char,
+    plain: KeySquare,
+}
+
+impl FourSquare {
+    pub fn new(top_right: &str, bottom_left: &str, filler: char) -> Result<Self, SquareError> {
+        Ok(Self {
+            top_right: KeySquare::new(top_right),
+            bottom_left: KeySquare::new(bottom_left),
+            filler: key_square::filler(filler)?,
+            plain: KeySquare::plain(),
+        })
+    }
+
+    /// The four squares as laid out on the page, row by row.
+    pub fn squares(&self) -> [[&KeySquare; 2]; 2] {
+        [
+            [&self.plain, &self.top_right],
+            [&self.bottom_left, &self.plain],
+        ]
+    }
+
+    fn transform(&self, text: &str, from: [&KeySquare; 2], to: [&KeySquare; 2]) -> String {
+        join(
+            digraphs(text, self.filler, false)
+                .into_iter()
+                .map(|(a, b)| {
+                    let (row_a, column_a) = from[0].position(a);
+                    let (row_b, column_b) = from[1].position(b);
+                    (to[0].at(row_a, column_b), to[1].at(row_b, column_a))
+                }),
+        )
+    }
+}
+
+impl CipherTraits for FourSquare {
+    fn encrypt(&self, plain_text: &str) -> String {
+        self.transform(
+            plain_text,
+            [&self.plain, &self.plain],
+            [&self.top_right, &self.bottom_left],
+        )
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        self.transform(
+            cipher_text,
+            [&self.top_right, &self.bottom_left],
+            [&self.plain, &self.plain],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bad_filler_fails() {
+        assert_eq!(
+            TwoSquare::new("A", "B", '!').unwrap_err(),
+            SquareError::InvalidFiller('!')
+        );
+        assert_eq!(
+            FourSquare::new("A", "B", 'j').unwrap_err(),
+            SquareError::InvalidFiller('j')
+        );
+    }
+
+    #[test]
+    fn two_square_encrypt_decrypt() {
+        let cipher = TwoSquare::new("EXAMPLE", "KEYWORD", 'X').unwrap();
+        let encrypted = cipher.encrypt("Help me Obi Wan Kenobi");
+
+        assert_eq!(encrypted, "HECMXWSRKYXPHWNODG");
+        assert_eq!(cipher.decrypt(&encrypted), "HELPMEOBIWANKENOBI");
+    }
+
+    #[test]
+    fn four_square_encrypt_decrypt() {
+        let cipher = FourSquare::new("EXAMPLE", "KEYWORD", 'X').unwrap();
+        let encrypted = cipher.encrypt("Help me Obi Wan Kenobi");
+
+        assert_eq!(encrypted, "FYNFNEHWBXAFFOKHMD");
+        assert_eq!(cipher.decrypt(&encrypted), "HELPMEOBIWANKENOBI");
+        assert_eq!(cipher.decrypt(&cipher.encrypt("odd")), "ODDX");
+    }
+}
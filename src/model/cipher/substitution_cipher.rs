@@ -0,0 +1,154 @@
+use std::fmt;
+
+use super::cipher_traits::CipherTraits;
+
+#[derive(Debug, PartialEq)]
+pub enum SubstitutionError {
+    EmptyKeyword,
+    WrongLength(usize),
+    RepeatedLetter(char),
+}
+
+impl fmt::Display for SubstitutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubstitutionError::EmptyKeyword => write!(f, "The keyword needs at least one letter"),
+            SubstitutionError::WrongLength(length) => {
+                write!(f, "A mixed alphabet needs 26 letters, not {length}")
+            }
+            SubstitutionError::RepeatedLetter(letter) => {
+                write!(f, "{letter} appears twice in the alphabet")
+            }
+        }
+    }
+}
+
+/// Swaps each letter for the one in the same place in a mixed alphabet.
+#[derive(Debug)]
+pub struct Substitution {
+    /// The letters A to Z stand for, in order.
+    pub alphabet: [u8; 26],
+    inverse: [u8; 26],
+}
+
+impl Substitution {
+    /// Uses a full mixed alphabet, i.e. "QWERTYUIOPASDFGHJKLZXCVBNM".
+    pub fn with_alphabet(alphabet: &str) -> Result<Self, SubstitutionError> {
+        let letters: Vec<u8> = alphabet
+            .bytes()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase() - b'A')
+            .collect();
+        if letters.len() != 26 {
+            return Err(SubstitutionError::WrongLength(letters.len()));
+        }
+
+        let mut mixed = [0; 26];
+        let mut inverse = [None; 26];
+        for (plain, &letter) in letters.iter().enumerate() {
+            if inverse[letter as usize].is_some() {
+                return Err(SubstitutionError::RepeatedLetter((b'A' + letter) as char));
+            }
+            mixed[plain] = letter;
+            inverse[letter as usize] = Some(plain as u8);
+        }
+
+        Ok(Self {
+            alphabet: mixed,
+            inverse: inverse.map(|plain| plain.unwrap_or_default()),
+        })
+    }
+
+    /// Mixes the alphabet with a keyword: its letters first, repeats dropped, then the rest
+    /// of the alphabet in order. "ZEBRAS" gives ZEBRASCDFGHIJKLMNOPQTUVWXY.
+    pub fn keyed(keyword: &str) -> Result<Self, SubstitutionError> {
+        let keyword: Vec<u8> = keyword
+            .bytes()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if keyword.is_empty() {
+            return Err(SubstitutionError::EmptyKeyword);
+        }
+
+        let mut alphabet: Vec<u8> = Vec::with_capacity(26);
+        for letter in keyword.into_iter().chain(b'A'..=b'Z') {
+            if !alphabet.contains(&letter) {
+                alphabet.push(letter);
+            }
+        }
+        Self::with_alphabet(&String::from_utf8_lossy(&alphabet))
+    }
+
+    fn map_char(c: char, table: &[u8; 26]) -> char {
+        if c.is_ascii_uppercase() {
+            (b'A' + table[(c as u8 - b'A') as usize]) as char
+        } else if c.is_ascii_lowercase() {
+            (b'a' + table[(c as u8 - b'a') as usize]) as char
+        } else {
+            c
+        }
+    }
+}
+
+impl CipherTraits for Substitution {
+    fn encrypt(&self, plain_text: &str) -> String {
+        plain_text
+            .chars()
+            .map(|c| Substitution::map_char(c, &self.alphabet))
+            .collect()
+    }
+
+    fn decrypt(&self, cipher_text: &str) -> String {
+        cipher_text
+            .chars()
+            .map(|c| Substitution::map_char(c, &self.inverse))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_keyword_fails() {
+        assert_eq!(
+            Substitution::keyed(" 42 ").unwrap_err(),
+            SubstitutionError::EmptyKeyword
+        );
+    }
+
+    #[test]
+    fn bad_alphabets_fail() {
+        assert_eq!(
+            Substitution::with_alphabet("ABC").unwrap_err(),
+            SubstitutionError::WrongLength(3)
+        );
+        assert_eq!(
+            Substitution::with_alphabet("AACDEFGHIJKLMNOPQRSTUVWXYZ").unwrap_err(),
+            SubstitutionError::RepeatedLetter('A')
+        );
+    }
+
+    #[test]
+    fn keyword_mixes_the_alphabet() {
+        let cipher = Substitution::keyed("Zebras").unwrap();
+        assert_eq!(
+            cipher.encrypt("ABCDEFGHIJKLMNOPQRSTUVWXYZ"),
+            "ZEBRASCDFGHIJKLMNOPQTUVWXY"
+        );
+        assert_eq!(cipher.encrypt("Flee at once."), "Siaa zq lkba.");
+    }
+
+    #[test]
+    fn substitution_encrypt_decrypt() {
+        let plain = "Attack at dawn!";
+        for cipher in [
+            Substitution::keyed("KEYWORD").unwrap(),
+            Substitution::with_alphabet("QWERTYUIOPASDFGHJKLZXCVBNM").unwrap(),
+        ] {
+            assert_eq!(cipher.decrypt(&cipher.encrypt(plain)), plain);
+        }
+    }
+}
@@ -0,0 +1,15 @@
+/// Reads `text` out in `order`, where `order[k]` is the position of the k-th character
+/// to read. `order` must be a permutation of `0..text.chars().count()`.
+pub fn transpose(text: &str, order: &[usize]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    order.iter().map(|&position| chars[position]).collect()
+}
+
+/// Undoes `transpose` with the same `order`.
+pub fn untranspose(text: &str, order: &[usize]) -> String {
+    let mut chars = vec!['\0'; order.len()];
+    for (c, &position) in text.chars().zip(order) {
+        chars[position] = c;
+    }
+    chars.into_iter().collect()
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use super::cipher_traits::CipherTraits;
 
 #[derive(Debug, PartialEq)]
@@ -5,14 +7,65 @@ pub enum VigenereError {
     EmptyKey,
 }
 
+/// How the key is laid against the text and which way it shifts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VigenereMode {
+    /// The keyword repeats, adding to the text.
+    #[default]
+    Repeating,
+    /// The keyword is followed by the plaintext itself.
+    PlaintextAutokey,
+    /// The keyword is followed by the ciphertext.
+    CiphertextAutokey,
+    /// The text is taken from the key, so encrypting and decrypting are the same.
+    Beaufort,
+    /// The key is taken from the text, the reverse of the repeating mode.
+    VariantBeaufort,
+    /// The key is a long source text, i.e. a page of a book, repeating only if the message
+    /// outruns it.
+    RunningKey,
+}
+
+impl VigenereMode {
+    pub const ALL: [VigenereMode; 6] = [
+        VigenereMode::Repeating,
+        VigenereMode::PlaintextAutokey,
+        VigenereMode::CiphertextAutokey,
+        VigenereMode::Beaufort,
+        VigenereMode::VariantBeaufort,
+        VigenereMode::RunningKey,
+    ];
+}
+
+impl fmt::Display for VigenereMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VigenereMode::Repeating => write!(f, "Repeating key"),
+            VigenereMode::PlaintextAutokey => write!(f, "Autokey (plaintext)"),
+            VigenereMode::CiphertextAutokey => write!(f, "Autokey (ciphertext)"),
+            VigenereMode::Beaufort => write!(f, "Beaufort"),
+            VigenereMode::VariantBeaufort => write!(f, "Variant Beaufort"),
+            VigenereMode::RunningKey => write!(f, "Running key"),
+        }
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct Vigenere {
     pub keyword: String,
+    pub mode: VigenereMode,
     key: Vec<u8>,
 }
 
 impl Vigenere {
     pub fn new(keyword: impl Into<String>) -> Result<Self, VigenereError> {
+        Self::with_mode(keyword, VigenereMode::Repeating)
+    }
+
+    pub fn with_mode(
+        keyword: impl Into<String>,
+        mode: VigenereMode,
+    ) -> Result<Self, VigenereError> {
         let keyword = keyword.into();
 
         let key: Vec<u8> = keyword
@@ -25,24 +78,44 @@ impl Vigenere {
             return Err(VigenereError::EmptyKey);
         }
 
-        Ok(Self { keyword, key })
+        Ok(Self { keyword, mode, key })
+    }
+
+    /// Whether the key is long enough for `text` without repeating, as a running key should be.
+    pub fn key_covers(&self, text: &str) -> bool {
+        text.chars().filter(char::is_ascii_alphabetic).count() <= self.key.len()
     }
 
     pub fn transform(&self, plain_text: &str, decrypt: bool) -> String {
         let mut result = String::new();
         let mut key_index = 0;
+        let mut feedback = Vec::new();
 
         for c in plain_text.chars() {
             if c.is_ascii_alphabetic() {
                 let is_upper = c.is_uppercase();
                 let base = if is_upper { b'A' } else { b'a' };
                 let offset = c as u8 - base;
-                let key = &self.key[key_index % self.key.len()];
-                let shift = if decrypt {
-                    (26 + offset - key) % 26
-                } else {
-                    (offset + key) % 26
+                let key = match self.mode {
+                    VigenereMode::PlaintextAutokey | VigenereMode::CiphertextAutokey => self
+                        .key
+                        .get(key_index)
+                        .unwrap_or_else(|| &feedback[key_index - self.key.len()]),
+                    _ => &self.key[key_index % self.key.len()],
+                };
+                let shift = match (self.mode, decrypt) {
+                    (VigenereMode::Beaufort, _) => (26 + key - offset) % 26,
+                    (VigenereMode::VariantBeaufort, true) => (offset + key) % 26,
+                    (VigenereMode::VariantBeaufort, false) | (_, true) => (26 + offset - key) % 26,
+                    _ => (offset + key) % 26,
                 };
+                match (self.mode, decrypt) {
+                    (VigenereMode::PlaintextAutokey, false)
+                    | (VigenereMode::CiphertextAutokey, true) => feedback.push(offset),
+                    (VigenereMode::PlaintextAutokey, true)
+                    | (VigenereMode::CiphertextAutokey, false) => feedback.push(shift),
+                    _ => {}
+                }
                 result.push((base + shift) as char);
                 key_index += 1;
             } else {
@@ -109,4 +182,54 @@ mod tests {
         let decrypted = v.decrypt(&encrypted);
         assert_eq!(decrypted, plain);
     }
+
+    #[test]
+    fn every_mode_round_trips() {
+        let plain = "Attack at dawn, then retreat to the docks!";
+        for mode in VigenereMode::ALL {
+            let v = Vigenere::with_mode("Lemon", mode).unwrap();
+            let encrypted = v.encrypt(plain);
+            assert_ne!(encrypted, plain, "{mode}");
+            assert_eq!(v.decrypt(&encrypted), plain, "{mode}");
+        }
+    }
+
+    #[test]
+    fn autokey_feeds_back_the_chosen_text() {
+        let plain = Vigenere::with_mode("B", VigenereMode::PlaintextAutokey).unwrap();
+        assert_eq!(plain.encrypt("ABC"), "BBD");
+        let cipher = Vigenere::with_mode("B", VigenereMode::CiphertextAutokey).unwrap();
+        assert_eq!(cipher.encrypt("ABC"), "BCE");
+
+        let v = Vigenere::with_mode("QUEENLY", VigenereMode::PlaintextAutokey).unwrap();
+        assert_eq!(v.encrypt("ATTACKATDAWN"), "QNXEPVYTWTWP");
+    }
+
+    #[test]
+    fn beaufort_is_its_own_inverse() {
+        let v = Vigenere::with_mode("FORTIFICATION", VigenereMode::Beaufort).unwrap();
+        let encrypted = v.encrypt("DEFENDTHEEASTWALLOFTHECASTLE");
+        assert_eq!(encrypted, "CKMPVCPVWPIWUJOGIUAPVWRIWUUK");
+        assert_eq!(v.encrypt(&encrypted), "DEFENDTHEEASTWALLOFTHECASTLE");
+    }
+
+    #[test]
+    fn variant_beaufort_reverses_the_repeating_key() {
+        let repeating = Vigenere::new("KEY").unwrap();
+        let variant = Vigenere::with_mode("KEY", VigenereMode::VariantBeaufort).unwrap();
+        assert_eq!(variant.encrypt("Attack"), repeating.decrypt("Attack"));
+        assert_eq!(variant.decrypt("Attack"), repeating.encrypt("Attack"));
+    }
+
+    #[test]
+    fn running_key_covers_messages_up_to_its_length() {
+        let v = Vigenere::with_mode(
+            "It was the best of times, it was the worst of times",
+            VigenereMode::RunningKey,
+        )
+        .unwrap();
+        assert_eq!(v.encrypt("Meet at dawn"), "Uxat sm kexr");
+        assert!(v.key_covers("Meet at dawn"));
+        assert!(!v.key_covers(&"a".repeat(50)));
+    }
 }
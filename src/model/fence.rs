@@ -1,6 +1,28 @@
+pub mod bulk;
+pub mod compare;
+pub mod generate;
+pub mod haggle;
+pub mod roll;
+pub mod saturation;
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::catalog::{Category, LootBag};
+use super::heat::{Heat, HeatTolerance};
+use super::ledger::Ledger;
+use super::money::Money;
+use super::region::{Location, Market};
+use bulk::VolumeTiers;
+use generate::Personality;
+use saturation::{Glut, MAX_DROP};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "u64")]
 pub struct Percentage(pub u64);
 
 #[derive(Debug, PartialEq, Clone)]
@@ -34,45 +56,419 @@ impl Percentage {
         }
     }
 
-    fn apply_to(&self, base: u64) -> u64 {
-        base * self.0 / Self::VALUE_SCALE
+    /// Scales `base` by this percentage. The product is worked out in 128 bits, so only a
+    /// result that cannot fit in a `u64` is an error.
+    pub fn apply_to(&self, base: u64, rounding: RoundingMode) -> Result<u64, PriceError> {
+        let scale = u128::from(Self::VALUE_SCALE);
+        let scaled = u128::from(base) * u128::from(self.0);
+        let (quotient, remainder) = (scaled / scale, scaled % scale);
+
+        let round_up = match rounding {
+            RoundingMode::Truncate => false,
+            RoundingMode::Nearest => remainder * 2 >= scale,
+            RoundingMode::HalfEven => {
+                remainder * 2 > scale || (remainder * 2 == scale && quotient % 2 == 1)
+            }
+            RoundingMode::FenceFavour => remainder > 0,
+        };
+
+        u64::try_from(quotient + u128::from(round_up)).map_err(|_| PriceError::Overflow)
+    }
+
+    /// Moves the percentage `points` tenths of a percent towards the item's value, never past it.
+    pub fn toward_value(&self, points: u64) -> Percentage {
+        match self.0.cmp(&Self::VALUE_SCALE) {
+            Ordering::Greater => Percentage(self.0.saturating_sub(points).max(Self::VALUE_SCALE)),
+            Ordering::Less => Percentage(self.0.saturating_add(points).min(Self::VALUE_SCALE)),
+            Ordering::Equal => self.clone(),
+        }
+    }
+
+    /// Moves the percentage `points` tenths of a percent in the fence's favour on `side`.
+    pub fn with_surcharge(&self, side: Side, points: u64) -> Percentage {
+        match side {
+            Side::Buy => Percentage(self.0.saturating_sub(points)),
+            Side::Sell => Percentage(self.0.saturating_add(points).min(Self::MAX)),
+        }
+    }
+
+    /// Lowers the percentage by `points` tenths of a percent, whichever side it is on.
+    pub fn lowered(&self, points: u64) -> Percentage {
+        Percentage(self.0.saturating_sub(points))
+    }
+
+    /// Applies a market's modifier on `side`. Positive points are a surcharge, negative
+    /// ones move the percentage the party's way.
+    pub fn in_market(&self, side: Side, points: i64) -> Percentage {
+        let other = match side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        match points < 0 {
+            true => self.with_surcharge(other, points.unsigned_abs()),
+            false => self.with_surcharge(side, points.unsigned_abs()),
+        }
     }
 }
 
-pub fn parse_human_percentage(input: &str) -> Result<Percentage, PercentageError> {
-    let trimmed = input.trim().trim_end_matches('%');
+impl TryFrom<u64> for Percentage {
+    type Error = PercentageError;
 
-    let value: f64 = trimmed
-        .parse()
-        .map_err(|_| PercentageError::InvalidFormat)?;
+    fn try_from(percent: u64) -> Result<Self, Self::Error> {
+        Self::try_new(percent)
+    }
+}
 
-    let scaled = (value * 10.0).round() as u64;
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundingMode {
+    #[default]
+    Truncate,
+    Nearest,
+    HalfEven,
+    /// Always rounds up, so fractions go to the fence.
+    FenceFavour,
+}
 
-    let final_markup = scaled + 1000;
+impl RoundingMode {
+    pub const ALL: [RoundingMode; 4] = [
+        RoundingMode::Truncate,
+        RoundingMode::Nearest,
+        RoundingMode::HalfEven,
+        RoundingMode::FenceFavour,
+    ];
+}
 
-    Percentage::try_new(final_markup)
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RoundingMode::Truncate => "Truncate",
+            RoundingMode::Nearest => "Nearest",
+            RoundingMode::HalfEven => "Half even",
+            RoundingMode::FenceFavour => "Fence's favour",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PriceError {
+    Overflow,
+}
+
+impl fmt::Display for PriceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceError::Overflow => write!(f, "Price is too large to calculate"),
+        }
+    }
+}
+
+/// Reads a percentage of an item's value. "10%" and "+10%" are a markup on top of the
+/// value, "-40%" a discount, "110% of value" the whole price and "x1.1" a multiplier.
+impl FromStr for Percentage {
+    type Err = PercentageError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim().to_ascii_lowercase();
+        let number = |text: &str| -> Result<f64, PercentageError> {
+            let value: f64 = text
+                .trim()
+                .trim_end_matches('%')
+                .trim()
+                .parse()
+                .map_err(|_| PercentageError::InvalidFormat)?;
+            match value.is_finite() {
+                true => Ok(value),
+                false => Err(PercentageError::InvalidFormat),
+            }
+        };
+
+        let scaled = if let Some(multiplier) = input.strip_prefix('x') {
+            number(multiplier)? * Self::VALUE_SCALE as f64
+        } else if let Some(total) = input.strip_suffix("of value") {
+            number(total)? * 10.0
+        } else {
+            Self::VALUE_SCALE as f64 + number(&input)? * 10.0
+        };
+
+        let scaled = scaled.round();
+        if scaled < Self::MIN as f64 || scaled > Self::MAX as f64 {
+            return Err(PercentageError::OutOfRange {
+                min: Self::MIN,
+                max: Self::MAX,
+            });
+        }
+        Percentage::try_new(scaled as u64)
+    }
 }
 
 impl fmt::Display for Percentage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}% of value", Tenths(self.0))
+    }
+}
+
+/// Formats a value stored in tenths, i.e. 125 as "12.5".
+pub struct Tenths(pub u64);
+
+impl fmt::Display for Tenths {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let whole = self.0 / 10;
         let fraction = self.0 % 10;
 
         if fraction == 0 {
-            write!(f, "{whole}% markup")
+            write!(f, "{whole}")
         } else {
-            write!(f, "{whole}.{fraction}% markup")
+            write!(f, "{whole}.{fraction}")
+        }
+    }
+}
+
+pub(crate) fn parse_tenths(input: &str) -> Option<u64> {
+    let value: f64 = input.trim().trim_end_matches('%').parse().ok()?;
+    (value >= 0.0).then(|| (value * 10.0).round() as u64)
+}
+
+/// How much a fence's markups drop as the party's reputation with it grows.
+/// Reductions are in tenths of a percent, the same scale as `Percentage`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ReputationCurve {
+    /// No change with reputation, which is how fences priced before curves existed.
+    #[default]
+    Flat,
+    Linear {
+        per_point: u64,
+    },
+    Stepped {
+        tiers: Vec<(u8, u64)>,
+    },
+    Diminishing {
+        max: u64,
+        half_at: u8,
+    },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ReputationCurveError {
+    InvalidFormat,
+}
+
+impl fmt::Display for ReputationCurveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReputationCurveError::InvalidFormat => write!(
+                f,
+                "Use flat, linear 0.1, stepped 10:2,50:5 or diminishing 10 50"
+            ),
+        }
+    }
+}
+
+impl ReputationCurve {
+    pub fn reduction(&self, reputation: u8) -> u64 {
+        let rep = u64::from(reputation);
+        match self {
+            ReputationCurve::Flat => 0,
+            ReputationCurve::Linear { per_point } => per_point.saturating_mul(rep),
+            ReputationCurve::Stepped { tiers } => tiers
+                .iter()
+                .filter(|(min_rep, _)| reputation >= *min_rep)
+                .map(|(_, reduction)| *reduction)
+                .max()
+                .unwrap_or(0),
+            ReputationCurve::Diminishing { max, half_at } => max
+                .saturating_mul(rep)
+                .checked_div(rep + u64::from(*half_at))
+                .unwrap_or(0),
+        }
+    }
+}
+
+impl FromStr for ReputationCurve {
+    type Err = ReputationCurveError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim().to_ascii_lowercase();
+        let (kind, args) = input.split_once(' ').unwrap_or((&input, ""));
+        let invalid = || ReputationCurveError::InvalidFormat;
+
+        match kind {
+            "flat" => Ok(ReputationCurve::Flat),
+            "linear" => Ok(ReputationCurve::Linear {
+                per_point: parse_tenths(args).ok_or_else(invalid)?,
+            }),
+            "stepped" => {
+                let tiers = args
+                    .split(',')
+                    .map(|tier| {
+                        let (rep, reduction) = tier.split_once(':')?;
+                        Some((rep.trim().parse().ok()?, parse_tenths(reduction)?))
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(invalid)?;
+                Ok(ReputationCurve::Stepped { tiers })
+            }
+            "diminishing" => {
+                let (max, half_at) = args.trim().split_once(' ').ok_or_else(invalid)?;
+                Ok(ReputationCurve::Diminishing {
+                    max: parse_tenths(max).ok_or_else(invalid)?,
+                    half_at: half_at.trim().parse().map_err(|_| invalid())?,
+                })
+            }
+            _ => Err(invalid()),
         }
     }
 }
 
-#[derive(Clone, Debug)]
+impl fmt::Display for ReputationCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReputationCurve::Flat => write!(f, "flat"),
+            ReputationCurve::Linear { per_point } => write!(f, "linear {}", Tenths(*per_point)),
+            ReputationCurve::Stepped { tiers } => {
+                let tiers: Vec<String> = tiers
+                    .iter()
+                    .map(|(rep, reduction)| format!("{rep}:{}", Tenths(*reduction)))
+                    .collect();
+                write!(f, "stepped {}", tiers.join(","))
+            }
+            ReputationCurve::Diminishing { max, half_at } => {
+                write!(f, "diminishing {} {half_at}", Tenths(*max))
+            }
+        }
+    }
+}
+
+/// Which way the goods move, from the fence's side of the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    /// The fence buys from the party and pays below value.
+    Buy,
+    /// The fence sells to the party and charges above value.
+    Sell,
+}
+
+impl Side {
+    pub const ALL: [Side; 2] = [Side::Buy, Side::Sell];
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Buy => write!(f, "Pays"),
+            Side::Sell => write!(f, "Charges"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spread {
+    pub lowest: Percentage,
+    pub avg: Percentage,
+    pub highest: Percentage,
+}
+
+impl Spread {
+    fn default_buy() -> Self {
+        Self {
+            lowest: Percentage(500),
+            avg: Percentage(600),
+            highest: Percentage(700),
+        }
+    }
+
+    /// Checks that lowest <= avg <= highest.
+    pub fn check(&self, side: Side) -> Vec<FenceError> {
+        let mut errors = Vec::new();
+        if self.lowest.0 > self.avg.0 {
+            errors.push(FenceError::LowestAboveAverage(side));
+        }
+        if self.avg.0 > self.highest.0 {
+            errors.push(FenceError::AverageAboveHighest(side));
+        }
+        errors
+    }
+}
+
+/// The parts of a fence a user types in, for pinning errors next to the right input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenceField {
+    Name,
+    Reputation,
+    Lowest(Side),
+    Average(Side),
+    Highest(Side),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum FenceError {
+    EmptyName,
+    DuplicateName(String),
+    ReputationOutOfRange { max: u8 },
+    LowestAboveAverage(Side),
+    AverageAboveHighest(Side),
+}
+
+impl FenceError {
+    pub fn field(&self) -> FenceField {
+        match self {
+            FenceError::EmptyName | FenceError::DuplicateName(_) => FenceField::Name,
+            FenceError::ReputationOutOfRange { .. } => FenceField::Reputation,
+            FenceError::LowestAboveAverage(side) => FenceField::Lowest(*side),
+            FenceError::AverageAboveHighest(side) => FenceField::Highest(*side),
+        }
+    }
+}
+
+impl fmt::Display for FenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenceError::EmptyName => write!(f, "Fence needs a name"),
+            FenceError::DuplicateName(name) => write!(f, "There is already a fence called {name}"),
+            FenceError::ReputationOutOfRange { max } => {
+                write!(f, "Reputation must be a whole number from 0 to {max}")
+            }
+            FenceError::LowestAboveAverage(_) => write!(f, "Lowest must not be above the average"),
+            FenceError::AverageAboveHighest(_) => {
+                write!(f, "Highest must not be below the average")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Fence {
     pub name: String,
     pub reputation: u8,
     pub avg_markup: Percentage,
     pub lowest_markup: Percentage,
     pub highest_markup: Percentage,
+    /// What the fence pays for goods the party sells. The markups above are what it charges.
+    #[serde(default = "Spread::default_buy")]
+    pub buy: Spread,
+    #[serde(default)]
+    pub reputation_curve: ReputationCurve,
+    #[serde(default)]
+    pub rounding: RoundingMode,
+    #[serde(default)]
+    pub heat_tolerance: HeatTolerance,
+    #[serde(default)]
+    pub ledger: Ledger,
+    #[serde(default)]
+    pub volume_tiers: VolumeTiers,
+    /// Most the fence can pay out in a week before it has to raise more coin.
+    #[serde(default)]
+    pub weekly_cash: Option<Money>,
+    #[serde(default)]
+    pub location: Option<Location>,
+    #[serde(default)]
+    pub personality: Option<Personality>,
+    /// Categories of goods the fence is known for, and deals in on better terms.
+    #[serde(default)]
+    pub specialties: Vec<Category>,
+    /// Categories of goods the fence won't touch.
+    #[serde(default)]
+    pub refused: Vec<Category>,
 }
 
 impl Default for Fence {
@@ -84,6 +480,17 @@ impl Default for Fence {
             avg_markup: Percentage(1100),
             lowest_markup: Percentage(1080),
             highest_markup: Percentage(1205),
+            buy: Spread::default_buy(),
+            reputation_curve: ReputationCurve::default(),
+            rounding: RoundingMode::default(),
+            heat_tolerance: HeatTolerance::default(),
+            ledger: Ledger::default(),
+            volume_tiers: VolumeTiers::default(),
+            weekly_cash: None,
+            location: None,
+            personality: None,
+            specialties: Vec::new(),
+            refused: Vec::new(),
         }
     }
 }
@@ -102,19 +509,326 @@ impl Fence {
             avg_markup,
             lowest_markup,
             highest_markup,
+            buy: Spread::default_buy(),
+            reputation_curve: ReputationCurve::default(),
+            rounding: RoundingMode::default(),
+            heat_tolerance: HeatTolerance::default(),
+            ledger: Ledger::default(),
+            volume_tiers: VolumeTiers::default(),
+            weekly_cash: None,
+            location: None,
+            personality: None,
+            specialties: Vec::new(),
+            refused: Vec::new(),
+        }
+    }
+
+    pub const MAX_REPUTATION: u8 = 100;
+    /// How much better a fence deals in its specialties, in tenths of a percent.
+    pub const SPECIALTY_EDGE: i64 = 50;
+
+    /// Builds a fence from validated parts, reporting every problem at once. `taken_names`
+    /// are the names of the other fences in the campaign.
+    pub fn try_new(
+        name: &str,
+        reputation: u8,
+        sell: Spread,
+        buy: Spread,
+        taken_names: &[&str],
+    ) -> Result<Self, Vec<FenceError>> {
+        let errors: Vec<FenceError> = Self::check_name(name, taken_names)
+            .err()
+            .into_iter()
+            .chain(Self::check_reputation(reputation).err())
+            .chain(sell.check(Side::Sell))
+            .chain(buy.check(Side::Buy))
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self {
+            buy,
+            ..Self::new(name.trim(), reputation, sell.avg, sell.lowest, sell.highest)
+        })
+    }
+
+    pub fn check_name(name: &str, taken_names: &[&str]) -> Result<(), FenceError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(FenceError::EmptyName);
+        }
+        match taken_names
+            .iter()
+            .any(|taken| taken.trim().eq_ignore_ascii_case(name))
+        {
+            true => Err(FenceError::DuplicateName(name.to_string())),
+            false => Ok(()),
         }
     }
 
-    pub fn avg_markup_price(&self, base_price: u64) -> u64 {
-        self.avg_markup.apply_to(base_price)
+    pub fn check_reputation(reputation: u8) -> Result<(), FenceError> {
+        match reputation <= Self::MAX_REPUTATION {
+            true => Ok(()),
+            false => Err(FenceError::ReputationOutOfRange {
+                max: Self::MAX_REPUTATION,
+            }),
+        }
+    }
+
+    fn adjusted(&self, markup: &Percentage) -> Percentage {
+        markup.toward_value(self.reputation_curve.reduction(self.reputation))
+    }
+
+    pub fn spread(&self, side: Side) -> Spread {
+        match side {
+            Side::Buy => self.buy.clone(),
+            Side::Sell => Spread {
+                lowest: self.lowest_markup.clone(),
+                avg: self.avg_markup.clone(),
+                highest: self.highest_markup.clone(),
+            },
+        }
+    }
+
+    /// The spread after reputation has pulled it towards the item's value.
+    pub fn adjusted_spread(&self, side: Side) -> Spread {
+        let spread = self.spread(side);
+        Spread {
+            lowest: self.adjusted(&spread.lowest),
+            avg: self.adjusted(&spread.avg),
+            highest: self.adjusted(&spread.highest),
+        }
+    }
+
+    /// Rounding for `side`. Rounding in the fence's favour rounds down what it pays.
+    fn rounding_for(&self, side: Side) -> RoundingMode {
+        match (self.rounding, side) {
+            (RoundingMode::FenceFavour, Side::Buy) => RoundingMode::Truncate,
+            (rounding, _) => rounding,
+        }
+    }
+
+    pub fn adjusted_avg_markup(&self) -> Percentage {
+        self.adjusted(&self.avg_markup)
+    }
+
+    pub fn adjusted_lowest_markup(&self) -> Percentage {
+        self.adjusted(&self.lowest_markup)
+    }
+
+    pub fn adjusted_highest_markup(&self) -> Percentage {
+        self.adjusted(&self.highest_markup)
     }
 
-    pub fn lowest_markup_price(&self, base_price: u64) -> u64 {
-        self.lowest_markup.apply_to(base_price)
+    /// Prices are counted in the campaign's smallest denomination, see `Money`.
+    pub fn avg_markup_price(&self, base_price: u64) -> Result<u64, PriceError> {
+        self.adjusted_avg_markup()
+            .apply_to(base_price, self.rounding)
     }
-    pub fn highest_markup_price(&self, base_price: u64) -> u64 {
-        self.highest_markup.apply_to(base_price)
+
+    pub fn lowest_markup_price(&self, base_price: u64) -> Result<u64, PriceError> {
+        self.adjusted_lowest_markup()
+            .apply_to(base_price, self.rounding)
+    }
+    pub fn highest_markup_price(&self, base_price: u64) -> Result<u64, PriceError> {
+        self.adjusted_highest_markup()
+            .apply_to(base_price, self.rounding)
     }
+
+    /// The fence's prices for an item worth `base_price`, in the `market` it works in.
+    pub fn price_band(
+        &self,
+        side: Side,
+        base_price: u64,
+        market: &Market,
+    ) -> Result<PriceBand, PriceError> {
+        self.surcharged_band(side, base_price, 0, 0, market.points())
+    }
+
+    /// Prices with a heat `surcharge` and a saturation `drop`, both in tenths of a percent,
+    /// in a market that moves prices by `points`.
+    fn surcharged_band(
+        &self,
+        side: Side,
+        base_price: u64,
+        surcharge: u64,
+        drop: u64,
+        points: i64,
+    ) -> Result<PriceBand, PriceError> {
+        let spread = self.adjusted_spread(side);
+        let rounding = self.rounding_for(side);
+        let price = |percentage: &Percentage| {
+            percentage
+                .with_surcharge(side, surcharge)
+                .lowered(drop)
+                .in_market(side, points)
+                .apply_to(base_price, rounding)
+        };
+        Ok(PriceBand {
+            low: price(&spread.lowest)?,
+            avg: price(&spread.avg)?,
+            high: price(&spread.highest)?,
+        })
+    }
+
+    /// How `category` sits with the fence: `Some` edge in the party's favour, in tenths of a
+    /// percent, or `None` if the fence refuses it.
+    pub fn edge_for(&self, category: Category) -> Option<i64> {
+        match (
+            self.refused.contains(&category),
+            self.specialties.contains(&category),
+        ) {
+            (true, _) => None,
+            (false, true) => Some(Self::SPECIALTY_EDGE),
+            (false, false) => Some(0),
+        }
+    }
+
+    /// Quotes every entry in `loot` as of in-game `day`. Goods of a refused category or too
+    /// hot for the fence are refused, priced at nothing and left out of the total. Specialties
+    /// get better prices. Goods of a category the fence is already sitting on fetch less, and
+    /// when it is buying, every unit it takes on lowers the price of the next.
+    pub fn quote_loot(
+        &self,
+        side: Side,
+        loot: &LootBag,
+        day: u32,
+        market: &Market,
+    ) -> Result<LootQuote, PriceError> {
+        let mut gluts: BTreeMap<Category, Glut> = BTreeMap::new();
+        let mut lines = Vec::with_capacity(loot.entries.len());
+        for entry in &loot.entries {
+            let heat = entry.heat_on(day);
+            let category = entry.item.category;
+            let edge = self.edge_for(category);
+            let (surcharge, refusal) = match (edge, self.heat_tolerance.surcharge(heat)) {
+                (None, _) => (0, Some(Refusal::Category(category))),
+                (Some(_), Some(surcharge)) => (surcharge, None),
+                (Some(_), None) => (
+                    0,
+                    Some(Refusal::TooHot {
+                        heat,
+                        limit: self.heat_tolerance.limit,
+                    }),
+                ),
+            };
+            let points = market.points().saturating_sub(edge.unwrap_or(0));
+            let glut = *gluts
+                .entry(category)
+                .or_insert_with(|| self.glut(category, day));
+            let base = entry.item.base_value.0;
+            let band =
+                |glut: Glut| self.surcharged_band(side, base, surcharge, glut.drop(), points);
+
+            let (unit, total) = match (&refusal, side) {
+                (Some(_), _) => (PriceBand::default(), PriceBand::default()),
+                (None, Side::Sell) => {
+                    let unit = band(glut)?;
+                    (unit.clone(), unit.times(entry.quantity)?)
+                }
+                (None, Side::Buy) => {
+                    gluts.insert(category, glut.plus(entry.quantity));
+                    let mut total = PriceBand::default();
+                    for taken in 0..entry.quantity {
+                        let price = band(glut.plus(taken))?;
+                        if glut.plus(taken).drop() == MAX_DROP {
+                            total = total.plus(&price.times(entry.quantity - taken)?)?;
+                            break;
+                        }
+                        total = total.plus(&price)?;
+                    }
+                    (band(glut)?, total)
+                }
+            };
+            lines.push(LootLine {
+                name: entry.item.name.clone(),
+                category,
+                quantity: entry.quantity,
+                unit,
+                total,
+                heat,
+                surcharge,
+                glut,
+                refusal,
+            });
+        }
+        let total = lines
+            .iter()
+            .try_fold(PriceBand::default(), |sum, line| sum.plus(&line.total))?;
+
+        Ok(LootQuote { lines, total })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PriceBand {
+    pub low: u64,
+    pub avg: u64,
+    pub high: u64,
+}
+
+impl PriceBand {
+    fn times(&self, quantity: u32) -> Result<PriceBand, PriceError> {
+        let quantity = u64::from(quantity);
+        let times = |price: u64| price.checked_mul(quantity).ok_or(PriceError::Overflow);
+        Ok(PriceBand {
+            low: times(self.low)?,
+            avg: times(self.avg)?,
+            high: times(self.high)?,
+        })
+    }
+
+    fn plus(&self, other: &PriceBand) -> Result<PriceBand, PriceError> {
+        let plus = |a: u64, b: u64| a.checked_add(b).ok_or(PriceError::Overflow);
+        Ok(PriceBand {
+            low: plus(self.low, other.low)?,
+            avg: plus(self.avg, other.avg)?,
+            high: plus(self.high, other.high)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootLine {
+    pub name: String,
+    pub category: Category,
+    pub quantity: u32,
+    pub unit: PriceBand,
+    pub total: PriceBand,
+    pub heat: Heat,
+    /// Risk surcharge for the heat, in tenths of a percent.
+    pub surcharge: u64,
+    /// Goods of the same category the fence already had before this line.
+    pub glut: Glut,
+    pub refusal: Option<Refusal>,
+}
+
+/// Why a fence won't deal in something.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Refusal {
+    TooHot { heat: Heat, limit: Heat },
+    Category(Category),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::TooHot { heat, limit } => {
+                write!(
+                    f,
+                    "too hot (heat {heat}, won't touch anything over {limit})"
+                )
+            }
+            Refusal::Category(category) => write!(f, "won't deal in {category}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootQuote {
+    pub lines: Vec<LootLine>,
+    pub total: PriceBand,
 }
 
 #[cfg(test)]
@@ -133,11 +847,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn out_of_range_percentage_fails_to_deserialize() {
+        assert!(serde_json::from_str::<Percentage>("100000000").is_err());
+        assert_eq!(serde_json::from_str::<Percentage>("1100").unwrap().0, 1100);
+    }
+
     #[test]
     fn default_fence_avg_markup_succeeds() {
         let fence = Fence::default();
         let base_price = 100;
-        let result = fence.avg_markup_price(base_price);
+        let result = fence.avg_markup_price(base_price).unwrap();
         assert_eq!(110, result);
     }
 
@@ -145,7 +865,7 @@ mod tests {
     fn default_fence_lowest_markup_succeeds() {
         let fence = Fence::default();
         let base_price = 100;
-        let result = fence.lowest_markup_price(base_price);
+        let result = fence.lowest_markup_price(base_price).unwrap();
         assert_eq!(108, result);
     }
 
@@ -153,7 +873,7 @@ mod tests {
     fn default_fence_highest_markup_succeeds() {
         let fence = Fence::default();
         let base_price = 1000;
-        let result = fence.highest_markup_price(base_price);
+        let result = fence.highest_markup_price(base_price).unwrap();
         assert_eq!(1205, result);
     }
 
@@ -161,7 +881,440 @@ mod tests {
     fn integar_trunc_works_as_intended() {
         let fence = Fence::default();
         let base_price = 100;
-        let result = fence.highest_markup_price(base_price);
+        let result = fence.highest_markup_price(base_price).unwrap();
         assert_eq!(120, result);
     }
+
+    #[test]
+    fn default_curve_keeps_prices_from_before_reputation_curves() {
+        let fence = Fence {
+            reputation: 10,
+            ..Fence::default()
+        };
+        assert_eq!(
+            fence.price_band(Side::Sell, 1000, &Market::default()),
+            Ok(PriceBand {
+                low: 1080,
+                avg: 1100,
+                high: 1205
+            })
+        );
+    }
+
+    #[test]
+    fn linear_curve_lowers_markup_with_reputation() {
+        let fence = Fence {
+            reputation: 50,
+            reputation_curve: ReputationCurve::Linear { per_point: 1 },
+            ..Fence::default()
+        };
+        assert_eq!(fence.adjusted_avg_markup().0, 1050);
+        assert_eq!(fence.avg_markup_price(100), Ok(105));
+    }
+
+    #[test]
+    fn stepped_curve_uses_highest_reached_tier() {
+        let curve = ReputationCurve::Stepped {
+            tiers: vec![(10, 20), (50, 50)],
+        };
+        assert_eq!(curve.reduction(5), 0);
+        assert_eq!(curve.reduction(10), 20);
+        assert_eq!(curve.reduction(80), 50);
+    }
+
+    #[test]
+    fn diminishing_curve_approaches_max() {
+        let curve = ReputationCurve::Diminishing {
+            max: 100,
+            half_at: 50,
+        };
+        assert_eq!(curve.reduction(0), 0);
+        assert_eq!(curve.reduction(50), 50);
+        assert!(curve.reduction(255) < 100);
+    }
+
+    #[test]
+    fn reputation_never_drops_markup_below_base() {
+        let fence = Fence {
+            reputation: 255,
+            reputation_curve: ReputationCurve::Linear { per_point: 10 },
+            ..Fence::default()
+        };
+        assert_eq!(fence.adjusted_highest_markup().0, 1000);
+    }
+
+    #[test]
+    fn reputation_curve_text_round_trips() {
+        for text in [
+            "flat",
+            "linear 0.5",
+            "stepped 10:2,50:5.5",
+            "diminishing 10 50",
+        ] {
+            let curve: ReputationCurve = text.parse().unwrap();
+            assert_eq!(curve.to_string(), text);
+        }
+        assert_eq!(
+            "linear x".parse::<ReputationCurve>(),
+            Err(ReputationCurveError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn loot_quote_totals_every_line() {
+        use crate::model::catalog::Catalog;
+
+        let catalog = Catalog::default();
+        let mut loot = LootBag::default();
+        loot.add(&catalog.items[0], 1);
+        loot.add(&catalog.items[0], 1);
+        loot.add(&catalog.items[1], 1);
+
+        let quote = Fence::default()
+            .quote_loot(Side::Sell, &loot, 1, &Market::default())
+            .unwrap();
+
+        assert_eq!(quote.lines.len(), 2);
+        assert_eq!(quote.lines[0].unit.avg, 5_500);
+        assert_eq!(quote.lines[0].total.avg, 11_000);
+        assert_eq!(quote.lines[1].total.avg, 13_200);
+        assert_eq!(quote.total.avg, 24_200);
+        assert_eq!(quote.total.low, 10_800 + 12_960);
+    }
+
+    #[test]
+    fn hot_loot_is_surcharged_then_refused() {
+        use crate::model::catalog::{Category, Item, Rarity};
+        use crate::model::money::Money;
+
+        let hot = Item {
+            heat: Heat(50),
+            ..Item::new("Crown", Category::Jewelry, Money(1000), 1.0, Rarity::Rare)
+        };
+        let mut loot = LootBag::default();
+        loot.add(&hot, 1);
+        let fence = Fence {
+            heat_tolerance: HeatTolerance {
+                comfort: Heat(20),
+                limit: Heat(40),
+                risk: ReputationCurve::Linear { per_point: 10 },
+            },
+            ..Fence::default()
+        };
+
+        let refused = fence
+            .quote_loot(Side::Buy, &loot, 1, &Market::default())
+            .unwrap();
+        assert_eq!(
+            refused.lines[0].refusal,
+            Some(Refusal::TooHot {
+                heat: Heat(50),
+                limit: Heat(40)
+            })
+        );
+        assert_eq!(refused.total, PriceBand::default());
+
+        // Three days later the crown has cooled to 35: 15 points over comfort is 15% off
+        // what the fence pays, and 15% on top of what it charges.
+        let buying = fence
+            .quote_loot(Side::Buy, &loot, 4, &Market::default())
+            .unwrap();
+        assert_eq!(buying.lines[0].refusal, None);
+        assert_eq!(buying.lines[0].surcharge, 150);
+        assert_eq!(buying.total.avg, 450);
+
+        let selling = fence
+            .quote_loot(Side::Sell, &loot, 4, &Market::default())
+            .unwrap();
+        assert_eq!(selling.total.avg, 1250);
+    }
+
+    #[test]
+    fn specialties_get_better_prices_and_refused_categories_are_refused() {
+        use crate::model::catalog::{Item, Rarity};
+        use crate::model::money::Money;
+
+        let painting = Item::new("Painting", Category::Art, Money(1000), 1.0, Rarity::Common);
+        let wand = Item::new("Wand", Category::Magic, Money(1000), 1.0, Rarity::Rare);
+        let mut loot = LootBag::default();
+        loot.add(&painting, 1);
+        loot.add(&wand, 1);
+        let fence = Fence {
+            specialties: vec![Category::Art],
+            refused: vec![Category::Magic],
+            ..Fence::default()
+        };
+
+        let pays = fence
+            .quote_loot(Side::Buy, &loot, 1, &Market::default())
+            .unwrap();
+        assert_eq!(pays.lines[0].unit.avg, 650);
+        assert_eq!(
+            pays.lines[1].refusal,
+            Some(Refusal::Category(Category::Magic))
+        );
+        assert_eq!(pays.total.avg, 650);
+
+        let charges = fence
+            .quote_loot(Side::Sell, &loot, 1, &Market::default())
+            .unwrap();
+        assert_eq!(charges.lines[0].unit.avg, 1050);
+    }
+
+    #[test]
+    fn market_modifiers_apply_on_top_of_markups() {
+        use crate::model::region::PriceModifier;
+
+        let market = |points| Market {
+            modifiers: vec![PriceModifier {
+                name: "Crackdown".into(),
+                points,
+            }],
+        };
+        let fence = Fence {
+            reputation: 50,
+            reputation_curve: ReputationCurve::Linear { per_point: 1 },
+            ..Fence::default()
+        };
+        // Reputation first takes the average to 105% and 65%, then the market moves it.
+        let crackdown = market(100);
+        assert_eq!(
+            fence.price_band(Side::Sell, 100, &crackdown).unwrap().avg,
+            115
+        );
+        assert_eq!(
+            fence.price_band(Side::Buy, 100, &crackdown).unwrap().avg,
+            55
+        );
+
+        let port = market(-50);
+        assert_eq!(fence.price_band(Side::Sell, 100, &port).unwrap().avg, 100);
+        assert_eq!(fence.price_band(Side::Buy, 100, &port).unwrap().avg, 70);
+    }
+
+    #[test]
+    fn try_new_builds_a_valid_fence() {
+        let fence = Fence::try_new(
+            "  Mags ",
+            40,
+            Fence::default().spread(Side::Sell),
+            Fence::default().buy,
+            &["Dave"],
+        )
+        .unwrap();
+
+        assert_eq!(fence.name, "Mags");
+        assert_eq!(fence.reputation, 40);
+        assert_eq!(fence.highest_markup, Percentage(1205));
+    }
+
+    #[test]
+    fn try_new_reports_every_problem() {
+        let sell = Spread {
+            lowest: Percentage(1300),
+            avg: Percentage(1200),
+            highest: Percentage(1100),
+        };
+        let buy = Spread {
+            lowest: Percentage(500),
+            avg: Percentage(800),
+            highest: Percentage(700),
+        };
+
+        let errors = Fence::try_new("dave", 101, sell, buy, &["Merchant", "Dave"]).unwrap_err();
+
+        assert_eq!(
+            errors,
+            vec![
+                FenceError::DuplicateName("dave".into()),
+                FenceError::ReputationOutOfRange { max: 100 },
+                FenceError::LowestAboveAverage(Side::Sell),
+                FenceError::AverageAboveHighest(Side::Sell),
+                FenceError::AverageAboveHighest(Side::Buy),
+            ]
+        );
+        assert_eq!(errors[4].field(), FenceField::Highest(Side::Buy));
+    }
+
+    #[test]
+    fn blank_name_is_rejected() {
+        assert_eq!(Fence::check_name("   ", &[]), Err(FenceError::EmptyName));
+        assert_eq!(Fence::check_name("Dave", &["Merchant"]), Ok(()));
+    }
+
+    #[test]
+    fn truncate_rounds_down() {
+        let markup = Percentage(1205);
+        assert_eq!(markup.apply_to(100, RoundingMode::Truncate), Ok(120));
+        assert_eq!(markup.apply_to(1000, RoundingMode::Truncate), Ok(1205));
+    }
+
+    #[test]
+    fn nearest_rounds_halves_up() {
+        assert_eq!(
+            Percentage(1205).apply_to(100, RoundingMode::Nearest),
+            Ok(121)
+        );
+        assert_eq!(
+            Percentage(1204).apply_to(100, RoundingMode::Nearest),
+            Ok(120)
+        );
+        assert_eq!(
+            Percentage(1206).apply_to(100, RoundingMode::Nearest),
+            Ok(121)
+        );
+    }
+
+    #[test]
+    fn half_even_rounds_halves_to_even() {
+        assert_eq!(
+            Percentage(1205).apply_to(100, RoundingMode::HalfEven),
+            Ok(120)
+        );
+        assert_eq!(
+            Percentage(1215).apply_to(100, RoundingMode::HalfEven),
+            Ok(122)
+        );
+        assert_eq!(
+            Percentage(1206).apply_to(100, RoundingMode::HalfEven),
+            Ok(121)
+        );
+    }
+
+    #[test]
+    fn fence_favour_always_rounds_up() {
+        assert_eq!(
+            Percentage(1201).apply_to(100, RoundingMode::FenceFavour),
+            Ok(121)
+        );
+        assert_eq!(
+            Percentage(1200).apply_to(100, RoundingMode::FenceFavour),
+            Ok(120)
+        );
+    }
+
+    #[test]
+    fn large_prices_do_not_wrap() {
+        let markup = Percentage(1100);
+        let base = u64::MAX / 100;
+        assert_eq!(
+            markup.apply_to(base, RoundingMode::Truncate),
+            Ok(base / 1000 * 1100 + base % 1000 * 1100 / 1000)
+        );
+        for mode in RoundingMode::ALL {
+            assert_eq!(markup.apply_to(u64::MAX, mode), Err(PriceError::Overflow));
+        }
+    }
+
+    #[test]
+    fn fence_prices_use_fence_rounding() {
+        let fence = Fence {
+            rounding: RoundingMode::Nearest,
+            ..Fence::default()
+        };
+        assert_eq!(fence.highest_markup_price(100), Ok(121));
+    }
+
+    #[test]
+    fn percentage_accepts_every_format() {
+        let parse = |text: &str| text.parse::<Percentage>();
+        assert_eq!(parse("10%"), Ok(Percentage(1100)));
+        assert_eq!(parse("+10%"), Ok(Percentage(1100)));
+        assert_eq!(parse("110% of value"), Ok(Percentage(1100)));
+        assert_eq!(parse("x1.1"), Ok(Percentage(1100)));
+        assert_eq!(parse("12.5"), Ok(Percentage(1125)));
+        assert_eq!(parse("-40%"), Ok(Percentage(600)));
+        assert_eq!(parse(" X0.6 "), Ok(Percentage(600)));
+    }
+
+    #[test]
+    fn percentage_rejects_bad_input() {
+        let parse = |text: &str| text.parse::<Percentage>();
+        assert_eq!(parse("ten"), Err(PercentageError::InvalidFormat));
+        assert_eq!(parse("x"), Err(PercentageError::InvalidFormat));
+        assert_eq!(parse("nan% of value"), Err(PercentageError::InvalidFormat));
+        assert_eq!(
+            parse("-150%"),
+            Err(PercentageError::OutOfRange {
+                min: Percentage::MIN,
+                max: Percentage::MAX
+            })
+        );
+        assert_eq!(
+            parse("x11"),
+            Err(PercentageError::OutOfRange {
+                min: Percentage::MIN,
+                max: Percentage::MAX
+            })
+        );
+    }
+
+    #[test]
+    fn percentage_display_round_trips() {
+        for value in Percentage::MIN..=Percentage::MAX {
+            let percentage = Percentage(value);
+            assert_eq!(percentage.to_string().parse(), Ok(percentage));
+        }
+        assert_eq!(Percentage(1205).to_string(), "120.5% of value");
+    }
+
+    #[test]
+    fn fence_pays_below_value_when_buying() {
+        let fence = Fence::default();
+        let band = fence
+            .price_band(Side::Buy, 1000, &Market::default())
+            .unwrap();
+        assert_eq!(
+            band,
+            PriceBand {
+                low: 500,
+                avg: 600,
+                high: 700
+            }
+        );
+    }
+
+    #[test]
+    fn reputation_raises_payouts_but_never_above_value() {
+        let fence = Fence {
+            reputation: 50,
+            reputation_curve: ReputationCurve::Linear { per_point: 1 },
+            ..Fence::default()
+        };
+        assert_eq!(fence.adjusted_spread(Side::Buy).avg, Percentage(650));
+
+        let fence = Fence {
+            reputation: 255,
+            reputation_curve: ReputationCurve::Linear { per_point: 10 },
+            ..Fence::default()
+        };
+        assert_eq!(fence.adjusted_spread(Side::Buy).highest, Percentage(1000));
+    }
+
+    #[test]
+    fn fence_favour_rounds_payouts_down() {
+        let fence = Fence {
+            rounding: RoundingMode::FenceFavour,
+            buy: Spread {
+                lowest: Percentage(555),
+                avg: Percentage(555),
+                highest: Percentage(555),
+            },
+            ..Fence::default()
+        };
+        assert_eq!(
+            fence
+                .price_band(Side::Buy, 100, &Market::default())
+                .unwrap()
+                .avg,
+            55
+        );
+        assert_eq!(
+            fence
+                .price_band(Side::Sell, 100, &Market::default())
+                .unwrap()
+                .high,
+            121
+        );
+    }
 }
@@ -0,0 +1,355 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::catalog::LootBag;
+use crate::model::money::{Denominations, Money};
+use crate::model::region::Market;
+
+use super::{Fence, Percentage, PriceBand, PriceError, Side, Tenths, parse_tenths};
+
+/// Once a single deal is worth `from` or more at base value, the fence's margin shrinks by
+/// `reduction` tenths of a percent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeTier {
+    pub from: Money,
+    pub reduction: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct VolumeTiers(pub Vec<VolumeTier>);
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum VolumeTierError {
+    InvalidFormat,
+}
+
+impl fmt::Display for VolumeTierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VolumeTierError::InvalidFormat => write!(f, "Use 100gp:2, 500gp:5 or leave blank"),
+        }
+    }
+}
+
+impl VolumeTiers {
+    /// The reduction for a deal worth `haul` at base value, from the highest tier reached.
+    pub fn reduction(&self, haul: Money) -> u64 {
+        self.0
+            .iter()
+            .filter(|tier| haul >= tier.from)
+            .map(|tier| tier.reduction)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Reads tiers like "100gp:2, 500gp:5", where the amounts are in the campaign's coins.
+    pub fn parse(input: &str, coins: &Denominations) -> Result<Self, VolumeTierError> {
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|tier| !tier.is_empty())
+            .map(|tier| {
+                let (from, reduction) = tier.split_once(':')?;
+                Some(VolumeTier {
+                    from: coins.parse(from).ok()?,
+                    reduction: parse_tenths(reduction)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(VolumeTiers)
+            .ok_or(VolumeTierError::InvalidFormat)
+    }
+
+    pub fn format(&self, coins: &Denominations) -> String {
+        self.0
+            .iter()
+            .map(|tier| format!("{}:{}", coins.format(tier.from), Tenths(tier.reduction)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// One loot entry in a bulk deal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkLine {
+    pub name: String,
+    pub quantity: u32,
+    pub base: Money,
+    pub price: PriceBand,
+    /// The part of the average price the fence can't pay until it has raised more coin.
+    pub deferred: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkQuote {
+    pub lines: Vec<BulkLine>,
+    pub total: PriceBand,
+    /// The volume reduction every line got, in tenths of a percent.
+    pub reduction: u64,
+    /// Cash the fence had left this week before the deal, if it has a limit.
+    pub cash_left: Option<Money>,
+    pub deferred: u64,
+}
+
+impl Fence {
+    /// Cash the fence can still pay out in the week of `day`, or `None` if it has no limit.
+    pub fn cash_left(&self, day: u32) -> Option<Money> {
+        let paid = self.ledger.week_of(day).paid;
+        self.weekly_cash
+            .map(|limit| Money(limit.0.saturating_sub(paid.0)))
+    }
+
+    /// Prices the loot the fence will take as a single deal on in-game `day`. Each line
+    /// starts from its `quote_loot` total, with heat, gluts and specialties already in. The
+    /// whole haul's base value sets the volume tier, which moves every price toward value,
+    /// and when the fence is buying, lines are paid in order until its cash for the week
+    /// runs out.
+    pub fn bulk_quote(
+        &self,
+        side: Side,
+        loot: &LootBag,
+        day: u32,
+        market: &Market,
+    ) -> Result<BulkQuote, PriceError> {
+        let quote = self.quote_loot(side, loot, day, market)?;
+        let taken: Vec<_> = loot
+            .entries
+            .iter()
+            .zip(quote.lines)
+            .filter(|(_, line)| line.refusal.is_none())
+            .map(|(entry, line)| {
+                let base = entry
+                    .item
+                    .base_value
+                    .0
+                    .checked_mul(u64::from(entry.quantity))
+                    .ok_or(PriceError::Overflow)?;
+                Ok((line, base))
+            })
+            .collect::<Result<_, PriceError>>()?;
+        let haul = taken
+            .iter()
+            .try_fold(0u64, |sum, (_, base)| sum.checked_add(*base))
+            .ok_or(PriceError::Overflow)?;
+        let reduction = self.volume_tiers.reduction(Money(haul));
+        let rounding = self.rounding_for(side);
+        let cash_left = match side {
+            Side::Buy => self.cash_left(day),
+            Side::Sell => None,
+        };
+
+        let mut cash = cash_left.map(|cash| cash.0);
+        let mut total = PriceBand::default();
+        let mut deferred = 0;
+        let mut lines = Vec::with_capacity(taken.len());
+        for (line, base) in taken {
+            let discount = Percentage(reduction).apply_to(base, rounding)?;
+            let toward_value = |price: u64| match price > base {
+                true => price.saturating_sub(discount).max(base),
+                false => price.saturating_add(discount).min(base),
+            };
+            let price = PriceBand {
+                low: toward_value(line.total.low),
+                avg: toward_value(line.total.avg),
+                high: toward_value(line.total.high),
+            };
+            let short = match &mut cash {
+                Some(cash) => {
+                    let paid = price.avg.min(*cash);
+                    *cash -= paid;
+                    price.avg - paid
+                }
+                None => 0,
+            };
+            total = total.plus(&price)?;
+            deferred += short;
+            lines.push(BulkLine {
+                name: line.name,
+                quantity: line.quantity,
+                base: Money(base),
+                price,
+                deferred: short,
+            });
+        }
+
+        Ok(BulkQuote {
+            lines,
+            total,
+            reduction,
+            cash_left,
+            deferred,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::catalog::{Category, Item, Rarity};
+    use crate::model::heat::{Heat, HeatTolerance};
+    use crate::model::ledger::{TradedItem, Transaction};
+
+    /// One item per base value, each of its own category so none glut the fence.
+    fn loot(bases: &[u64]) -> LootBag {
+        let mut loot = LootBag::default();
+        for (i, base) in bases.iter().enumerate() {
+            let item = Item::new(
+                &format!("Trinket {i}"),
+                Category::ALL[i],
+                Money(*base),
+                1.0,
+                Rarity::Common,
+            );
+            loot.add(&item, 1);
+        }
+        loot
+    }
+
+    fn fence() -> Fence {
+        Fence {
+            volume_tiers: VolumeTiers(vec![
+                VolumeTier {
+                    from: Money(1_000),
+                    reduction: 50,
+                },
+                VolumeTier {
+                    from: Money(5_000),
+                    reduction: 100,
+                },
+            ]),
+            ..Fence::default()
+        }
+    }
+
+    #[test]
+    fn bigger_hauls_reach_better_tiers() {
+        let small = fence()
+            .bulk_quote(Side::Buy, &loot(&[500]), 1, &Market::default())
+            .unwrap();
+        assert_eq!(small.reduction, 0);
+        assert_eq!(small.total.avg, 300);
+
+        let large = fence()
+            .bulk_quote(Side::Buy, &loot(&[3_000, 3_000]), 1, &Market::default())
+            .unwrap();
+        assert_eq!(large.reduction, 100);
+        assert_eq!(large.lines[0].price.avg, 2_100);
+        assert_eq!(large.total.avg, 4_200);
+
+        let charged = fence()
+            .bulk_quote(Side::Sell, &loot(&[1_000]), 1, &Market::default())
+            .unwrap();
+        assert_eq!(charged.total.avg, 1_050);
+    }
+
+    #[test]
+    fn weekly_cash_defers_what_the_fence_cannot_pay() {
+        let mut fence = Fence {
+            weekly_cash: Some(Money(1_000)),
+            ..Fence::default()
+        };
+        for (day, price) in [(1, 400), (7, 300), (8, 900)] {
+            fence.ledger.record(Transaction {
+                day,
+                session: 1,
+                character: "Vex".into(),
+                side: Side::Buy,
+                items: vec![TradedItem {
+                    name: "Ruby".into(),
+                    quantity: 1,
+                    category: None,
+                }],
+                quoted: Money(price),
+                price: Money(price),
+            });
+        }
+
+        let quote = fence
+            .bulk_quote(Side::Buy, &loot(&[500, 500]), 6, &Market::default())
+            .unwrap();
+        assert_eq!(quote.cash_left, Some(Money(300)));
+        assert_eq!(quote.lines[0].deferred, 0);
+        assert_eq!(quote.lines[1].deferred, 300);
+        assert_eq!(quote.deferred, 300);
+
+        // A new week, and the 900 paid on day 8 leaves 100.
+        assert_eq!(fence.cash_left(9), Some(Money(100)));
+        let selling = fence
+            .bulk_quote(Side::Sell, &loot(&[500]), 6, &Market::default())
+            .unwrap();
+        assert_eq!((selling.cash_left, selling.deferred), (None, 0));
+    }
+
+    #[test]
+    fn bulk_pays_no_more_than_the_loot_quote_for_hot_and_glutted_goods() {
+        let volume_tiers = fence().volume_tiers;
+        let crown = Item {
+            heat: Heat(50),
+            ..Item::new("Crown", Category::Jewelry, Money(1_000), 1.0, Rarity::Rare)
+        };
+        let vase = Item::new("Vase", Category::Art, Money(1_000), 1.0, Rarity::Common);
+        let mut loot = LootBag::default();
+        loot.add(&crown, 1);
+        loot.add(&vase, 1);
+        loot.add(&vase, 1);
+        let mut fence = Fence {
+            heat_tolerance: HeatTolerance {
+                comfort: Heat(20),
+                ..HeatTolerance::default()
+            },
+            ..Fence::default()
+        };
+        fence.ledger.record(Transaction {
+            day: 1,
+            session: 1,
+            character: "Vex".into(),
+            side: Side::Buy,
+            items: vec![TradedItem {
+                name: "Vase".into(),
+                quantity: 3,
+                category: Some(Category::Art),
+            }],
+            quoted: Money(1_800),
+            price: Money(1_800),
+        });
+
+        let quoted = fence
+            .quote_loot(Side::Buy, &loot, 1, &Market::default())
+            .unwrap();
+        assert!(quoted.lines[0].surcharge > 0);
+        assert!(quoted.lines[1].glut.0 > 0);
+
+        let bulk = fence
+            .bulk_quote(Side::Buy, &loot, 1, &Market::default())
+            .unwrap();
+        assert_eq!(bulk.reduction, 0);
+        assert_eq!(bulk.total, quoted.total);
+        assert!(bulk.total.avg < 3 * 600);
+
+        // A volume tier only moves the loot quote toward value.
+        let tiered = Fence {
+            volume_tiers,
+            ..fence
+        };
+        let bulk = tiered
+            .bulk_quote(Side::Buy, &loot, 1, &Market::default())
+            .unwrap();
+        assert_eq!(bulk.reduction, 50);
+        assert_eq!(bulk.total.avg, quoted.total.avg + 3 * 50);
+    }
+
+    #[test]
+    fn volume_tier_text_round_trips() {
+        let coins = Denominations::default();
+        let tiers = VolumeTiers::parse("5gp:5, 1pp 5gp:7.5", &coins).unwrap();
+        assert_eq!(tiers.0[1].from, Money(1_500));
+        assert_eq!(tiers.format(&coins), "5 gp:5, 1 pp 5 gp:7.5");
+        assert_eq!(VolumeTiers::parse(" ", &coins), Ok(VolumeTiers::default()));
+        assert_eq!(
+            VolumeTiers::parse("10gp", &coins),
+            Err(VolumeTierError::InvalidFormat)
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use crate::model::catalog::LootBag;
+use crate::model::region::Regions;
+
+use super::{Fence, LootLine, LootQuote, PriceBand, PriceError, Side};
+
+/// One fence's quote for the goods being compared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranking {
+    /// Where the fence sits in the campaign's fence list.
+    pub index: usize,
+    pub quote: LootQuote,
+}
+
+/// Quotes `loot` at every fence in its own market, best deal for the party first: fences that take
+/// everything before ones that refuse something, then the most paid when the fences buy
+/// or the least charged when they sell. Ties keep the fences' order.
+pub fn rank_fences(
+    fences: &[Fence],
+    regions: &Regions,
+    side: Side,
+    loot: &LootBag,
+    day: u32,
+) -> Result<Vec<Ranking>, PriceError> {
+    let mut rankings = fences
+        .iter()
+        .enumerate()
+        .map(|(index, fence)| {
+            Ok(Ranking {
+                index,
+                quote: fence.quote_loot(
+                    side,
+                    loot,
+                    day,
+                    &regions.market(fence.location.as_ref()),
+                )?,
+            })
+        })
+        .collect::<Result<Vec<_>, PriceError>>()?;
+
+    let refusals = |ranking: &Ranking| {
+        ranking
+            .quote
+            .lines
+            .iter()
+            .filter(|line| line.refusal.is_some())
+            .count()
+    };
+    match side {
+        Side::Buy => rankings.sort_by_key(|ranking| {
+            (
+                refusals(ranking),
+                std::cmp::Reverse(ranking.quote.total.avg),
+            )
+        }),
+        Side::Sell => rankings.sort_by_key(|ranking| (refusals(ranking), ranking.quote.total.avg)),
+    }
+    Ok(rankings)
+}
+
+/// One loot entry sent to the fence that treats it best.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub entry: usize,
+    pub fence: usize,
+    pub line: LootLine,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Split {
+    pub assignments: Vec<Assignment>,
+    /// Entries every fence refused.
+    pub unplaced: Vec<usize>,
+    pub total: PriceBand,
+}
+
+/// Splits `loot` across fences entry by entry, each going to the fence with the best
+/// average price for it.
+pub fn best_split(
+    fences: &[Fence],
+    regions: &Regions,
+    side: Side,
+    loot: &LootBag,
+    day: u32,
+) -> Result<Split, PriceError> {
+    let quotes = fences
+        .iter()
+        .map(|fence| fence.quote_loot(side, loot, day, &regions.market(fence.location.as_ref())))
+        .collect::<Result<Vec<_>, PriceError>>()?;
+    let mut split = Split::default();
+
+    for entry in 0..loot.entries.len() {
+        let offers = quotes
+            .iter()
+            .enumerate()
+            .map(|(fence, quote)| (fence, &quote.lines[entry]))
+            .filter(|(_, line)| line.refusal.is_none());
+        let best = match side {
+            Side::Buy => {
+                offers.max_by_key(|(fence, line)| (line.total.avg, std::cmp::Reverse(*fence)))
+            }
+            Side::Sell => offers.min_by_key(|(fence, line)| (line.total.avg, *fence)),
+        };
+
+        match best {
+            Some((fence, line)) => {
+                split.total = split.total.plus(&line.total)?;
+                split.assignments.push(Assignment {
+                    entry,
+                    fence,
+                    line: line.clone(),
+                });
+            }
+            None => split.unplaced.push(entry),
+        }
+    }
+    Ok(split)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::catalog::{Category, Item, Rarity};
+    use crate::model::fence::Percentage;
+    use crate::model::heat::{Heat, HeatTolerance};
+    use crate::model::money::Money;
+
+    fn fence(name: &str, buy_avg: u64, limit: u8) -> Fence {
+        let mut fence = Fence::new(
+            name,
+            0,
+            Percentage(1100),
+            Percentage(1050),
+            Percentage(1200),
+        );
+        fence.buy.avg = Percentage(buy_avg);
+        fence.heat_tolerance = HeatTolerance {
+            limit: Heat(limit),
+            ..HeatTolerance::default()
+        };
+        fence
+    }
+
+    fn loot() -> LootBag {
+        let mut loot = LootBag::default();
+        loot.add(
+            &Item::new(
+                "Candlestick",
+                Category::Art,
+                Money(1000),
+                1.0,
+                Rarity::Common,
+            ),
+            1,
+        );
+        loot.add(
+            &Item {
+                heat: Heat(60),
+                ..Item::new("Crown", Category::Jewelry, Money(2000), 1.0, Rarity::Rare)
+            },
+            1,
+        );
+        loot
+    }
+
+    #[test]
+    fn ranks_by_expected_payout() {
+        let fences = [fence("Low", 550, 100), fence("High", 650, 100)];
+
+        let rankings = rank_fences(&fences, &Regions::default(), Side::Buy, &loot(), 1).unwrap();
+
+        let order: Vec<usize> = rankings.iter().map(|ranking| ranking.index).collect();
+        assert_eq!(order, vec![1, 0]);
+        assert!(rankings[0].quote.total.avg > rankings[1].quote.total.avg);
+    }
+
+    #[test]
+    fn cheapest_fence_ranks_first_when_buying_from_them() {
+        let mut cheap = fence("Cheap", 600, 100);
+        cheap.avg_markup = Percentage(1060);
+        let fences = [fence("Dear", 600, 100), cheap];
+
+        let rankings = rank_fences(&fences, &Regions::default(), Side::Sell, &loot(), 1).unwrap();
+        assert_eq!(rankings[0].index, 1);
+    }
+
+    #[test]
+    fn split_sends_each_entry_to_its_best_taker() {
+        // "Picky" pays more but won't touch the hot crown.
+        let fences = [fence("Picky", 700, 40), fence("Bold", 600, 100)];
+
+        let split = best_split(&fences, &Regions::default(), Side::Buy, &loot(), 1).unwrap();
+
+        assert_eq!(split.assignments.len(), 2);
+        assert_eq!(split.assignments[0].fence, 0);
+        assert_eq!(split.assignments[1].fence, 1);
+        assert!(split.unplaced.is_empty());
+
+        let single = rank_fences(&fences, &Regions::default(), Side::Buy, &loot(), 1).unwrap();
+        assert_eq!(single[0].index, 1);
+        assert!(split.total.avg > single[0].quote.total.avg);
+    }
+
+    #[test]
+    fn split_reports_goods_nobody_takes() {
+        let fences = [fence("Picky", 700, 40)];
+
+        let split = best_split(&fences, &Regions::default(), Side::Buy, &loot(), 1).unwrap();
+
+        assert_eq!(split.unplaced, vec![1]);
+        assert_eq!(split.total.avg, 700);
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::model::catalog::Category;
+
+use super::{Fence, Percentage, Spread};
+
+/// How a fence carries itself at the table. Each one sets where its markups sit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Personality {
+    Greedy,
+    Cautious,
+    Jovial,
+    Paranoid,
+    Honest,
+    Ruthless,
+}
+
+impl Personality {
+    pub const ALL: [Personality; 6] = [
+        Personality::Greedy,
+        Personality::Cautious,
+        Personality::Jovial,
+        Personality::Paranoid,
+        Personality::Honest,
+        Personality::Ruthless,
+    ];
+
+    /// Typical (average, width) of what the fence charges and pays, in tenths of a percent.
+    fn markups(self) -> [(u64, u64); 2] {
+        match self {
+            Personality::Greedy => [(1250, 80), (500, 60)],
+            Personality::Cautious => [(1150, 40), (550, 40)],
+            Personality::Jovial => [(1100, 60), (620, 60)],
+            Personality::Paranoid => [(1200, 50), (500, 50)],
+            Personality::Honest => [(1080, 30), (650, 30)],
+            Personality::Ruthless => [(1300, 120), (450, 80)],
+        }
+    }
+}
+
+impl fmt::Display for Personality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Personality::Greedy => "Greedy",
+            Personality::Cautious => "Cautious",
+            Personality::Jovial => "Jovial",
+            Personality::Paranoid => "Paranoid",
+            Personality::Honest => "Honest",
+            Personality::Ruthless => "Ruthless",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Names to build generated fences from, i.e. "Mags" and "the Silent" for "Mags the Silent".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NameLists {
+    pub names: Vec<String>,
+    pub epithets: Vec<String>,
+}
+
+impl Default for NameLists {
+    fn default() -> Self {
+        let list = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        Self {
+            names: list(&[
+                "Mags", "Tobin", "Oswin", "Vera", "Kesh", "Dunstan", "Lira", "Grell", "Sable",
+                "Fen", "Marta", "Quill",
+            ]),
+            epithets: list(&[
+                "the Silent",
+                "Two-Coins",
+                "the Weasel",
+                "Halfhand",
+                "the Widow",
+                "Brass",
+                "of the Docks",
+                "the Honest",
+            ]),
+        }
+    }
+}
+
+impl NameLists {
+    /// Reads a comma separated list, skipping blanks.
+    pub fn parse_list(input: &str) -> Vec<String> {
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn format_list(names: &[String]) -> String {
+        names.join(", ")
+    }
+}
+
+/// Fields a re-roll leaves alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Locks {
+    pub name: bool,
+    pub reputation: bool,
+    pub personality: bool,
+    pub specialty: bool,
+    /// Both what the fence charges and what it pays.
+    pub markups: bool,
+}
+
+const MAX_START_REPUTATION: u8 = 30;
+const NAME_ATTEMPTS: usize = 20;
+
+/// Generates a fence from `seed`. The same seed and names always give the same fence.
+pub fn generate(names: &NameLists, seed: u64, taken_names: &[&str]) -> Fence {
+    reroll(
+        &Fence::default(),
+        Locks::default(),
+        names,
+        seed,
+        taken_names,
+    )
+}
+
+/// Generates a fence from `seed`, keeping the fields of `previous` that are locked.
+/// Markups follow the fence's personality, locked or not.
+pub fn reroll(
+    previous: &Fence,
+    locks: Locks,
+    names: &NameLists,
+    seed: u64,
+    taken_names: &[&str],
+) -> Fence {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let name = pick_name(names, taken_names, &mut rng);
+    let reputation = rng.random_range(0..=MAX_START_REPUTATION);
+    let personality = *Personality::ALL
+        .choose(&mut rng)
+        .unwrap_or(&Personality::Honest);
+    let specialty = *Category::ALL.choose(&mut rng).unwrap_or(&Category::Curios);
+
+    let personality = match locks.personality {
+        true => previous.personality.unwrap_or(personality),
+        false => personality,
+    };
+    let [sell, buy] = personality.markups();
+    let sell = spread(sell, 1010, 10_000, &mut rng);
+    let buy = spread(buy, 50, 950, &mut rng);
+
+    let mut fence = Fence {
+        personality: Some(personality),
+        specialties: vec![specialty],
+        buy,
+        ..Fence::new(&name, reputation, sell.avg, sell.lowest, sell.highest)
+    };
+    if locks.name {
+        fence.name = previous.name.clone();
+    }
+    if locks.reputation {
+        fence.reputation = previous.reputation;
+    }
+    if locks.specialty {
+        fence.specialties = previous.specialties.clone();
+    }
+    if locks.markups {
+        fence.lowest_markup = previous.lowest_markup.clone();
+        fence.avg_markup = previous.avg_markup.clone();
+        fence.highest_markup = previous.highest_markup.clone();
+        fence.buy = previous.buy.clone();
+    }
+    fence
+}
+
+/// A name no other fence has yet, falling back to numbering it if the lists run dry.
+fn pick_name(names: &NameLists, taken_names: &[&str], rng: &mut StdRng) -> String {
+    let mut draw = || {
+        let name = names.names.choose(rng).map_or("Fence", String::as_str);
+        match names.epithets.choose(rng) {
+            Some(epithet) => format!("{name} {epithet}"),
+            None => name.to_string(),
+        }
+    };
+    let free = |name: &str| Fence::check_name(name, taken_names).is_ok();
+    let mut name = draw();
+    for _ in 1..NAME_ATTEMPTS {
+        if free(&name) {
+            return name;
+        }
+        name = draw();
+    }
+    (2..)
+        .map(|n| format!("{name} {n}"))
+        .find(|numbered| free(numbered))
+        .unwrap_or(name)
+}
+
+/// A lowest <= avg <= highest band around `avg`, kept within `floor..=ceiling`.
+fn spread((avg, width): (u64, u64), floor: u64, ceiling: u64, rng: &mut StdRng) -> Spread {
+    let jitter = width / 2;
+    let avg = (avg + rng.random_range(0..=2 * jitter))
+        .saturating_sub(jitter)
+        .clamp(floor, ceiling);
+    let lowest = avg
+        .saturating_sub(rng.random_range(width / 2..=width))
+        .max(floor);
+    let highest = (avg + rng.random_range(width / 2..=width)).min(ceiling);
+    Spread {
+        lowest: Percentage(lowest),
+        avg: Percentage(avg),
+        highest: Percentage(highest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fence::Side;
+
+    #[test]
+    fn same_seed_gives_the_same_fence() {
+        let names = NameLists::default();
+        let fence = generate(&names, 7, &[]);
+
+        assert_eq!(fence.name, generate(&names, 7, &[]).name);
+        assert_eq!(fence.buy, generate(&names, 7, &[]).buy);
+        assert!(fence.personality.is_some());
+        assert_eq!(fence.specialties.len(), 1);
+        assert!(fence.reputation <= MAX_START_REPUTATION);
+    }
+
+    #[test]
+    fn generated_bands_are_consistent() {
+        let names = NameLists::default();
+        for seed in 0..500 {
+            let fence = generate(&names, seed, &[]);
+            assert!(fence.spread(Side::Sell).check(Side::Sell).is_empty());
+            assert!(fence.buy.check(Side::Buy).is_empty());
+            assert!(fence.lowest_markup.0 > 1000);
+            assert!(fence.buy.highest.0 < 1000);
+        }
+    }
+
+    #[test]
+    fn rerolls_keep_locked_fields() {
+        let names = NameLists::default();
+        let first = generate(&names, 1, &[]);
+        let locks = Locks {
+            name: true,
+            markups: true,
+            ..Locks::default()
+        };
+
+        let rerolled = (2..50)
+            .map(|seed| reroll(&first, locks, &names, seed, &[]))
+            .find(|fence| fence.reputation != first.reputation)
+            .unwrap();
+        assert_eq!(rerolled.name, first.name);
+        assert_eq!(rerolled.spread(Side::Sell), first.spread(Side::Sell));
+        assert_eq!(rerolled.buy, first.buy);
+    }
+
+    #[test]
+    fn names_avoid_fences_already_in_the_campaign() {
+        let names = NameLists {
+            names: vec!["Mags".into()],
+            epithets: Vec::new(),
+        };
+        assert_eq!(generate(&names, 3, &["Mags"]).name, "Mags 2");
+        assert_eq!(generate(&names, 3, &["mags", "Mags 2"]).name, "Mags 3");
+    }
+}
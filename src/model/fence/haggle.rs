@@ -0,0 +1,194 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::model::region::Market;
+
+use super::{Fence, PriceError, Side};
+
+#[derive(Debug, Clone)]
+pub struct HaggleSetup {
+    pub opening_ask: u64,
+    pub skill_modifier: i8,
+    /// A d20 rolled at the table for the first round. Later rounds are rolled from `seed`.
+    pub roll: Option<u8>,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HaggleRound {
+    pub round: u8,
+    pub roll: u8,
+    pub total: i16,
+    pub party_ask: u64,
+    pub fence_offer: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HaggleOutcome {
+    Deal { price: u64 },
+    WalkedAway,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Haggle {
+    pub rounds: Vec<HaggleRound>,
+    pub outcome: HaggleOutcome,
+}
+
+const MAX_ROUNDS: u8 = 6;
+const PATIENCE: u8 = 3;
+
+/// Haggles over an item the party is buying. The fence opens at its highest price and
+/// concedes towards the party's ask depending on each round's roll, but never goes below
+/// its lowest price. The party raises its ask a little every round. Failed rolls cost the
+/// fence's patience, and a natural 1 ends the deal on the spot.
+pub fn haggle(
+    fence: &Fence,
+    base_price: u64,
+    market: &Market,
+    setup: &HaggleSetup,
+) -> Result<Haggle, PriceError> {
+    let mut rng = StdRng::seed_from_u64(setup.seed);
+    let band = fence.price_band(Side::Sell, base_price, market)?;
+    // Saved fences aren't checked, so a lowest markup above the highest is read the right way
+    // round rather than trusted.
+    let floor = band.low.min(band.high);
+    let mut offer = band.low.max(band.high);
+    let mut ask = setup.opening_ask;
+    let mut patience = PATIENCE;
+    let mut rounds = Vec::new();
+
+    if ask >= offer {
+        return Ok(Haggle {
+            rounds,
+            outcome: HaggleOutcome::Deal { price: offer },
+        });
+    }
+
+    for round in 1..=MAX_ROUNDS {
+        let roll = match setup.roll {
+            Some(roll) if round == 1 => roll.clamp(1, 20),
+            _ => rng.random_range(1..=20),
+        };
+        let total = i16::from(roll) + i16::from(setup.skill_modifier);
+
+        let target = ask.max(floor);
+        let gap = offer.saturating_sub(target);
+        let concession = match total {
+            20.. => gap.div_ceil(2),
+            15..=19 => gap.div_ceil(3),
+            10..=14 => gap.div_ceil(4),
+            _ => 0,
+        };
+        offer -= concession;
+        if total < 10 {
+            patience -= 1;
+        }
+
+        rounds.push(HaggleRound {
+            round,
+            roll,
+            total,
+            party_ask: ask,
+            fence_offer: offer,
+        });
+
+        if roll == 1 || patience == 0 {
+            return Ok(Haggle {
+                rounds,
+                outcome: HaggleOutcome::WalkedAway,
+            });
+        }
+
+        if offer <= ask {
+            return Ok(Haggle {
+                rounds,
+                outcome: HaggleOutcome::Deal { price: offer },
+            });
+        }
+
+        ask += (offer - ask).div_ceil(4);
+    }
+
+    Ok(Haggle {
+        rounds,
+        outcome: HaggleOutcome::WalkedAway,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fence::Percentage;
+
+    fn setup(opening_ask: u64, roll: Option<u8>, seed: u64) -> HaggleSetup {
+        HaggleSetup {
+            opening_ask,
+            skill_modifier: 5,
+            roll,
+            seed,
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_haggle() {
+        let fence = Fence::default();
+        let first = haggle(&fence, 1000, &Market::default(), &setup(900, None, 42)).unwrap();
+        let second = haggle(&fence, 1000, &Market::default(), &setup(900, None, 42)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn ask_above_highest_price_deals_immediately() {
+        let fence = Fence::default();
+        let result = haggle(&fence, 1000, &Market::default(), &setup(2000, None, 1)).unwrap();
+        assert!(result.rounds.is_empty());
+        assert_eq!(result.outcome, HaggleOutcome::Deal { price: 1205 });
+    }
+
+    #[test]
+    fn table_roll_is_used_for_first_round() {
+        let fence = Fence::default();
+        let result = haggle(&fence, 1000, &Market::default(), &setup(900, Some(17), 7)).unwrap();
+        assert_eq!(result.rounds[0].roll, 17);
+        assert_eq!(result.rounds[0].total, 22);
+    }
+
+    #[test]
+    fn natural_one_walks_away() {
+        let fence = Fence::default();
+        let result = haggle(&fence, 1000, &Market::default(), &setup(900, Some(1), 7)).unwrap();
+        assert_eq!(result.rounds.len(), 1);
+        assert_eq!(result.outcome, HaggleOutcome::WalkedAway);
+    }
+
+    #[test]
+    fn deals_stay_within_fence_band() {
+        let fence = Fence::default();
+        for seed in 0..200 {
+            let result = haggle(&fence, 1000, &Market::default(), &setup(500, None, seed)).unwrap();
+            if let HaggleOutcome::Deal { price } = result.outcome {
+                assert!((1080..=1205).contains(&price), "seed {seed} gave {price}");
+            }
+            assert!(result.rounds.len() <= MAX_ROUNDS as usize);
+        }
+    }
+
+    #[test]
+    fn inverted_spread_haggles_within_its_band() {
+        let fence = Fence {
+            lowest_markup: Percentage(1300),
+            highest_markup: Percentage(1100),
+            ..Fence::default()
+        };
+        for seed in 0..200 {
+            let result = haggle(&fence, 1000, &Market::default(), &setup(500, None, seed)).unwrap();
+            if let HaggleOutcome::Deal { price } = result.outcome {
+                assert!((1100..=1300).contains(&price), "seed {seed} gave {price}");
+            }
+            for round in &result.rounds {
+                assert!((1100..=1300).contains(&round.fence_offer));
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Beta, Distribution};
+
+use super::PriceBand;
+
+/// How a rolled price spreads between a band's low and high. Both peak at the band's
+/// average, which is treated as the most likely price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriceDistribution {
+    #[default]
+    Triangular,
+    /// Bunches up around the average more tightly than `Triangular`.
+    Pert,
+}
+
+impl PriceDistribution {
+    pub const ALL: [PriceDistribution; 2] =
+        [PriceDistribution::Triangular, PriceDistribution::Pert];
+}
+
+impl fmt::Display for PriceDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceDistribution::Triangular => write!(f, "Triangular"),
+            PriceDistribution::Pert => write!(f, "PERT"),
+        }
+    }
+}
+
+/// Rolls a single price from `band`. The same seed always gives the same price.
+pub fn roll_price(band: &PriceBand, distribution: PriceDistribution, seed: u64) -> u64 {
+    sample(band, distribution, &mut StdRng::seed_from_u64(seed))
+}
+
+pub fn sample(band: &PriceBand, distribution: PriceDistribution, rng: &mut impl Rng) -> u64 {
+    let low = band.low.min(band.high);
+    let high = band.high.max(band.low);
+    if low == high {
+        return low;
+    }
+
+    let (a, b) = (low as f64, high as f64);
+    let c = (band.avg as f64).clamp(a, b);
+    let price = match distribution {
+        PriceDistribution::Triangular => {
+            let u: f64 = rng.random();
+            let split = (c - a) / (b - a);
+            match u < split {
+                true => a + (u * (b - a) * (c - a)).sqrt(),
+                false => b - ((1.0 - u) * (b - a) * (b - c)).sqrt(),
+            }
+        }
+        PriceDistribution::Pert => {
+            let alpha = 1.0 + 4.0 * (c - a) / (b - a);
+            let beta = 1.0 + 4.0 * (b - c) / (b - a);
+            match Beta::new(alpha, beta) {
+                Ok(shape) => a + shape.sample(rng) * (b - a),
+                Err(_) => c,
+            }
+        }
+    };
+
+    (price.round() as u64).clamp(low, high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 20_000;
+
+    fn band() -> PriceBand {
+        PriceBand {
+            low: 1_000,
+            avg: 1_200,
+            high: 2_000,
+        }
+    }
+
+    fn mean(distribution: PriceDistribution) -> f64 {
+        let mut rng = StdRng::seed_from_u64(7);
+        let total: u64 = (0..SAMPLES)
+            .map(|_| sample(&band(), distribution, &mut rng))
+            .sum();
+        total as f64 / SAMPLES as f64
+    }
+
+    #[test]
+    fn samples_stay_inside_the_band() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for distribution in PriceDistribution::ALL {
+            for _ in 0..SAMPLES {
+                let price = sample(&band(), distribution, &mut rng);
+                assert!((1_000..=2_000).contains(&price), "{distribution}: {price}");
+            }
+        }
+    }
+
+    #[test]
+    fn triangular_mean_is_average_of_corners() {
+        // (low + avg + high) / 3
+        let expected = (1_000.0 + 1_200.0 + 2_000.0) / 3.0;
+        assert!((mean(PriceDistribution::Triangular) - expected).abs() < 10.0);
+    }
+
+    #[test]
+    fn pert_mean_weights_the_average() {
+        // (low + 4 * avg + high) / 6
+        let expected = (1_000.0 + 4.0 * 1_200.0 + 2_000.0) / 6.0;
+        assert!((mean(PriceDistribution::Pert) - expected).abs() < 10.0);
+    }
+
+    #[test]
+    fn same_seed_rolls_same_price() {
+        for distribution in PriceDistribution::ALL {
+            assert_eq!(
+                roll_price(&band(), distribution, 42),
+                roll_price(&band(), distribution, 42)
+            );
+        }
+    }
+
+    #[test]
+    fn flat_band_always_rolls_its_price() {
+        let flat = PriceBand {
+            low: 500,
+            avg: 500,
+            high: 500,
+        };
+        for seed in 0..10 {
+            assert_eq!(roll_price(&flat, PriceDistribution::Pert, seed), 500);
+        }
+    }
+}
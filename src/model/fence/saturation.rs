@@ -0,0 +1,140 @@
+use crate::model::catalog::Category;
+
+use super::{Fence, Side};
+
+/// Units of one category a fence moves on every in-game day.
+pub const CLEARED_PER_DAY: u32 = 1;
+/// How far prices drop for every unit the fence is already sitting on, in tenths of a percent.
+pub const DROP_PER_UNIT: u64 = 20;
+pub const MAX_DROP: u64 = 400;
+
+/// Goods of one category a fence still has on its hands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Glut(pub u32);
+
+impl Glut {
+    /// How far the fence's prices drop for goods of the same category, in tenths of a percent.
+    pub fn drop(self) -> u64 {
+        u64::from(self.0)
+            .saturating_mul(DROP_PER_UNIT)
+            .min(MAX_DROP)
+    }
+
+    pub fn plus(self, units: u32) -> Glut {
+        Glut(self.0.saturating_add(units))
+    }
+}
+
+impl Fence {
+    /// How many goods of `category` the fence is still sitting on by `day`, worked out from
+    /// what it bought in its ledger. Only goods whose category is known count.
+    pub fn glut(&self, category: Category, day: u32) -> Glut {
+        let mut bought: Vec<(u32, u32)> = self
+            .ledger
+            .transactions
+            .iter()
+            .filter(|transaction| transaction.side == Side::Buy && transaction.day <= day)
+            .map(|transaction| {
+                let units = transaction
+                    .items
+                    .iter()
+                    .filter(|item| item.category == Some(category))
+                    .map(|item| item.quantity)
+                    .sum();
+                (transaction.day, units)
+            })
+            .collect();
+        bought.sort_by_key(|(day, _)| *day);
+
+        let cleared =
+            |units: u32, days: u32| units.saturating_sub(days.saturating_mul(CLEARED_PER_DAY));
+        let (units, last) = bought
+            .into_iter()
+            .fold((0u32, 0u32), |(units, last), (on, bought)| {
+                (cleared(units, on - last).saturating_add(bought), on)
+            });
+        Glut(cleared(units, day - last))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::catalog::{Item, LootBag, Rarity};
+    use crate::model::ledger::{TradedItem, Transaction};
+    use crate::model::money::Money;
+    use crate::model::region::Market;
+
+    fn sold(fence: &mut Fence, day: u32, category: Category, quantity: u32) {
+        fence.ledger.record(Transaction {
+            day,
+            session: 1,
+            character: "Vex".into(),
+            side: Side::Buy,
+            items: vec![TradedItem {
+                name: "Candlestick".into(),
+                quantity,
+                category: Some(category),
+            }],
+            quoted: Money(0),
+            price: Money(0),
+        });
+    }
+
+    #[test]
+    fn glut_builds_up_and_clears_over_time() {
+        let mut fence = Fence::default();
+        sold(&mut fence, 2, Category::Art, 3);
+        sold(&mut fence, 4, Category::Art, 2);
+        sold(&mut fence, 4, Category::Gems, 5);
+
+        assert_eq!(fence.glut(Category::Art, 1), Glut(0));
+        assert_eq!(fence.glut(Category::Art, 3), Glut(2));
+        // One of the first three is still around when two more arrive.
+        assert_eq!(fence.glut(Category::Art, 4), Glut(3));
+        assert_eq!(fence.glut(Category::Art, 6), Glut(1));
+        assert_eq!(fence.glut(Category::Art, 30), Glut(0));
+        assert_eq!(fence.glut(Category::Gems, 4), Glut(5));
+        assert_eq!(fence.glut(Category::Jewelry, 4), Glut(0));
+    }
+
+    #[test]
+    fn tenth_candlestick_fetches_less_than_the_first() {
+        let candlestick = Item::new(
+            "Candlestick",
+            Category::Art,
+            Money(1000),
+            1.0,
+            Rarity::Common,
+        );
+        let mut loot = LootBag::default();
+        for _ in 0..10 {
+            loot.add(&candlestick, 1);
+        }
+        let mut fence = Fence::default();
+        let fresh = fence
+            .quote_loot(Side::Buy, &loot, 1, &Market::default())
+            .unwrap();
+
+        // 60% of value for the first, 2% less for each one after it.
+        assert_eq!(fresh.lines[0].unit.avg, 600);
+        assert_eq!(
+            fresh.lines[0].total.avg,
+            (0..10).map(|k| 600 - 20 * k).sum::<u64>()
+        );
+
+        sold(&mut fence, 1, Category::Art, 10);
+        let glutted = fence
+            .quote_loot(Side::Buy, &loot, 1, &Market::default())
+            .unwrap();
+        assert_eq!(glutted.lines[0].glut, Glut(10));
+        assert_eq!(glutted.lines[0].unit.avg, 400);
+        assert!(glutted.total.avg < fresh.total.avg);
+
+        let charged = fence
+            .quote_loot(Side::Sell, &loot, 1, &Market::default())
+            .unwrap();
+        assert_eq!(charged.lines[0].unit.avg, 900);
+        assert_eq!(charged.total.avg, 9_000);
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::fence::ReputationCurve;
+
+/// How hot stolen goods are, from 0 (clean) to 100 (every watchman in town is looking for it).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "u8")]
+pub struct Heat(pub u8);
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum HeatError {
+    OutOfRange {
+        max: u8,
+    },
+    InvalidFormat,
+    /// A limit under the comfort level would refuse goods before ever surcharging them.
+    BelowComfort {
+        comfort: u8,
+    },
+}
+
+impl fmt::Display for HeatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeatError::OutOfRange { max } => write!(f, "Heat must be between 0 and {max}"),
+            HeatError::InvalidFormat => write!(f, "Heat must be a whole number"),
+            HeatError::BelowComfort { comfort } => {
+                write!(f, "The limit can't be below the comfort level of {comfort}")
+            }
+        }
+    }
+}
+
+impl Heat {
+    pub const MAX: u8 = 100;
+    /// Heat lost for every in-game day the goods lie low.
+    pub const DECAY_PER_DAY: u8 = 5;
+
+    pub fn try_new(heat: u8) -> Result<Self, HeatError> {
+        match heat <= Self::MAX {
+            true => Ok(Self(heat)),
+            false => Err(HeatError::OutOfRange { max: Self::MAX }),
+        }
+    }
+
+    pub fn decayed(self, days: u32) -> Heat {
+        let decay = u32::from(Self::DECAY_PER_DAY).saturating_mul(days);
+        Heat(u32::from(self.0).saturating_sub(decay) as u8)
+    }
+}
+
+impl TryFrom<u8> for Heat {
+    type Error = HeatError;
+
+    fn try_from(heat: u8) -> Result<Self, Self::Error> {
+        Self::try_new(heat)
+    }
+}
+
+impl FromStr for Heat {
+    type Err = HeatError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let heat: u64 = input.trim().parse().map_err(|_| HeatError::InvalidFormat)?;
+        u8::try_from(heat)
+            .map_err(|_| HeatError::OutOfRange { max: Self::MAX })
+            .and_then(Self::try_new)
+    }
+}
+
+impl fmt::Display for Heat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// How much heat a fence will put up with. Goods hotter than `comfort` carry a risk
+/// surcharge from `risk`, and anything hotter than `limit` is refused outright.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatTolerance {
+    pub comfort: Heat,
+    pub limit: Heat,
+    /// Surcharge in tenths of a percent by how far the heat is over `comfort`. Uses the
+    /// same curves as reputation.
+    pub risk: ReputationCurve,
+}
+
+impl Default for HeatTolerance {
+    fn default() -> Self {
+        Self {
+            comfort: Heat(20),
+            limit: Heat(80),
+            risk: ReputationCurve::Linear { per_point: 5 },
+        }
+    }
+}
+
+impl HeatTolerance {
+    pub fn check_limit(comfort: Heat, limit: Heat) -> Result<Heat, HeatError> {
+        match comfort <= limit {
+            true => Ok(limit),
+            false => Err(HeatError::BelowComfort { comfort: comfort.0 }),
+        }
+    }
+
+    /// The risk surcharge for goods at `heat`, or `None` if they are too hot to touch.
+    pub fn surcharge(&self, heat: Heat) -> Option<u64> {
+        match heat > self.limit {
+            true => None,
+            false => Some(self.risk.reduction(heat.0.saturating_sub(self.comfort.0))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heat_decays_to_zero() {
+        assert_eq!(Heat(60).decayed(0), Heat(60));
+        assert_eq!(Heat(60).decayed(3), Heat(45));
+        assert_eq!(Heat(60).decayed(u32::MAX), Heat(0));
+    }
+
+    #[test]
+    fn heat_parses_within_range() {
+        assert_eq!("40".parse(), Ok(Heat(40)));
+        assert_eq!(
+            "101".parse::<Heat>(),
+            Err(HeatError::OutOfRange { max: 100 })
+        );
+        assert_eq!(
+            "1000".parse::<Heat>(),
+            Err(HeatError::OutOfRange { max: 100 })
+        );
+        assert_eq!("warm".parse::<Heat>(), Err(HeatError::InvalidFormat));
+        assert!(serde_json::from_str::<Heat>("120").is_err());
+    }
+
+    #[test]
+    fn surcharge_grows_past_comfort_until_refusal() {
+        let tolerance = HeatTolerance::default();
+        assert_eq!(tolerance.surcharge(Heat(10)), Some(0));
+        assert_eq!(tolerance.surcharge(Heat(20)), Some(0));
+        assert_eq!(tolerance.surcharge(Heat(30)), Some(50));
+        assert_eq!(tolerance.surcharge(Heat(80)), Some(300));
+        assert_eq!(tolerance.surcharge(Heat(81)), None);
+    }
+
+    #[test]
+    fn limit_cant_sit_below_comfort() {
+        assert_eq!(HeatTolerance::check_limit(Heat(20), Heat(20)), Ok(Heat(20)));
+        assert_eq!(
+            HeatTolerance::check_limit(Heat(8), Heat(3)),
+            Err(HeatError::BelowComfort { comfort: 8 })
+        );
+    }
+}
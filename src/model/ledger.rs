@@ -0,0 +1,281 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::catalog::{Category, LootBag};
+use super::fence::Side;
+use super::money::Money;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradedItem {
+    pub name: String,
+    pub quantity: u32,
+    /// Known for goods from the loot bag or the catalog, and used to track market saturation.
+    #[serde(default)]
+    pub category: Option<Category>,
+}
+
+impl TradedItem {
+    pub fn from_loot(loot: &LootBag) -> Vec<TradedItem> {
+        loot.entries
+            .iter()
+            .map(|entry| TradedItem {
+                name: entry.item.name.clone(),
+                quantity: entry.quantity,
+                category: Some(entry.item.category),
+            })
+            .collect()
+    }
+
+    /// Reads a list like "Ruby x2, Gold ring". Items without a quantity count once.
+    pub fn parse_list(input: &str) -> Vec<TradedItem> {
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                let counted = item.rsplit_once(" x").and_then(|(name, quantity)| {
+                    Some((name.trim(), quantity.trim().parse().ok()?))
+                });
+                let (name, quantity) = counted.unwrap_or((item, 1));
+                TradedItem {
+                    name: name.to_string(),
+                    quantity,
+                    category: None,
+                }
+            })
+            .collect()
+    }
+
+    pub fn format_list(items: &[TradedItem]) -> String {
+        items
+            .iter()
+            .map(TradedItem::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for TradedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.quantity {
+            1 => write!(f, "{}", self.name),
+            quantity => write!(f, "{} x{quantity}", self.name),
+        }
+    }
+}
+
+/// One closed deal. `side` is the fence's side of the table, as in quotes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub day: u32,
+    pub session: u32,
+    pub character: String,
+    pub side: Side,
+    pub items: Vec<TradedItem>,
+    pub quoted: Money,
+    pub price: Money,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ledger {
+    pub transactions: Vec<Transaction>,
+}
+
+impl Ledger {
+    pub fn record(&mut self, transaction: Transaction) {
+        self.transactions.push(transaction);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.transactions.len() {
+            self.transactions.remove(index);
+        }
+    }
+
+    pub fn totals(&self) -> Totals {
+        Totals::of(&self.transactions)
+    }
+
+    /// Totals for the in-game week `day` falls in. Weeks start on day 1, 8, 15 and so on.
+    pub fn week_of(&self, day: u32) -> Totals {
+        let week = |day: u32| day.saturating_sub(1) / DAYS_PER_WEEK;
+        Totals::of(
+            self.transactions
+                .iter()
+                .filter(|transaction| week(transaction.day) == week(day)),
+        )
+    }
+}
+
+pub const DAYS_PER_WEEK: u32 = 7;
+
+/// Money that changed hands over some set of transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub deals: u32,
+    /// Paid out by fences for goods the party sold.
+    pub paid: Money,
+    /// Charged by fences for goods the party bought.
+    pub charged: Money,
+}
+
+impl Totals {
+    pub fn of<'a>(transactions: impl IntoIterator<Item = &'a Transaction>) -> Totals {
+        transactions
+            .into_iter()
+            .fold(Totals::default(), |mut totals, transaction| {
+                totals.deals += 1;
+                let sum = match transaction.side {
+                    Side::Buy => &mut totals.paid,
+                    Side::Sell => &mut totals.charged,
+                };
+                sum.0 = sum.0.saturating_add(transaction.price.0);
+                totals
+            })
+    }
+
+    /// Totals grouped by `key`, i.e. by character or by session.
+    pub fn by<'a, K: Ord>(
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+        key: impl Fn(&Transaction) -> K,
+    ) -> BTreeMap<K, Totals> {
+        let mut groups: BTreeMap<K, Vec<&Transaction>> = BTreeMap::new();
+        for transaction in transactions {
+            groups
+                .entry(key(transaction))
+                .or_default()
+                .push(transaction);
+        }
+        groups
+            .into_iter()
+            .map(|(key, transactions)| (key, Totals::of(transactions)))
+            .collect()
+    }
+}
+
+/// Writes every transaction as CSV, one row each, with prices in the smallest denomination.
+pub fn to_csv<'a>(rows: impl IntoIterator<Item = (&'a str, &'a Transaction)>) -> String {
+    let mut csv = String::from("fence,day,session,character,side,items,quoted,price\n");
+    for (fence, transaction) in rows {
+        let side = match transaction.side {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        };
+        let fields = [
+            csv_field(fence),
+            transaction.day.to_string(),
+            transaction.session.to_string(),
+            csv_field(&transaction.character),
+            side.to_string(),
+            csv_field(&TradedItem::format_list(&transaction.items)),
+            transaction.quoted.0.to_string(),
+            transaction.price.0.to_string(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deal(character: &str, session: u32, side: Side, price: u64) -> Transaction {
+        Transaction {
+            day: 1,
+            session,
+            character: character.into(),
+            side,
+            items: TradedItem::parse_list("Ruby x2, Gold ring"),
+            quoted: Money(price + 100),
+            price: Money(price),
+        }
+    }
+
+    #[test]
+    fn parses_and_formats_item_lists() {
+        let items = TradedItem::parse_list(" Ruby x2, Gold ring ,, Box of x-rays ");
+        assert_eq!(
+            items,
+            vec![
+                TradedItem {
+                    name: "Ruby".into(),
+                    quantity: 2,
+                    category: None,
+                },
+                TradedItem {
+                    name: "Gold ring".into(),
+                    quantity: 1,
+                    category: None,
+                },
+                TradedItem {
+                    name: "Box of x-rays".into(),
+                    quantity: 1,
+                    category: None,
+                },
+            ]
+        );
+        assert_eq!(
+            TradedItem::format_list(&items),
+            "Ruby x2, Gold ring, Box of x-rays"
+        );
+    }
+
+    #[test]
+    fn totals_split_paid_and_charged() {
+        let mut ledger = Ledger::default();
+        ledger.record(deal("Vex", 1, Side::Buy, 500));
+        ledger.record(deal("Vex", 1, Side::Sell, 300));
+        ledger.record(deal("Ash", 2, Side::Buy, 200));
+
+        assert_eq!(
+            ledger.totals(),
+            Totals {
+                deals: 3,
+                paid: Money(700),
+                charged: Money(300)
+            }
+        );
+    }
+
+    #[test]
+    fn totals_group_by_character_and_session() {
+        let transactions = [
+            deal("Vex", 1, Side::Buy, 500),
+            deal("Ash", 1, Side::Buy, 200),
+            deal("Vex", 2, Side::Sell, 300),
+        ];
+
+        let by_character = Totals::by(&transactions, |t| t.character.clone());
+        assert_eq!(by_character["Vex"].deals, 2);
+        assert_eq!(by_character["Vex"].paid, Money(500));
+        assert_eq!(by_character["Vex"].charged, Money(300));
+        assert_eq!(by_character["Ash"].paid, Money(200));
+
+        let by_session = Totals::by(&transactions, |t| t.session);
+        assert_eq!(by_session[&1].paid, Money(700));
+        assert_eq!(by_session[&2].charged, Money(300));
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        let transaction = deal("Vex \"the Quick\"", 3, Side::Sell, 1250);
+        let csv = to_csv([("Dave", &transaction)]);
+
+        assert_eq!(
+            csv,
+            "fence,day,session,character,side,items,quoted,price\n\
+             Dave,1,3,\"Vex \"\"the Quick\"\"\",sell,\"Ruby x2, Gold ring\",1350,1250\n"
+        );
+    }
+}
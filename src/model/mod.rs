@@ -1,2 +1,9 @@
+pub mod campaign;
+pub mod catalog;
 pub mod cipher;
 pub mod fence;
+pub mod heat;
+pub mod ledger;
+pub mod money;
+pub mod party;
+pub mod region;
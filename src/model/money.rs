@@ -0,0 +1,276 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// An amount of coin, counted in the campaign's smallest denomination.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Money(pub u64);
+
+impl From<u64> for Money {
+    fn from(value: u64) -> Self {
+        Money(value)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum MoneyError {
+    InvalidFormat,
+    UnknownDenomination(String),
+    Overflow,
+    MissingBaseUnit,
+    DuplicateDenomination(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::InvalidFormat => write!(f, "Invalid amount, try 12gp 5sp"),
+            MoneyError::UnknownDenomination(name) => write!(f, "Unknown denomination '{name}'"),
+            MoneyError::Overflow => write!(f, "Amount is too large"),
+            MoneyError::MissingBaseUnit => write!(f, "One denomination must be worth 1"),
+            MoneyError::DuplicateDenomination(name) => {
+                write!(f, "Denomination '{name}' is listed twice")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Denomination {
+    pub name: String,
+    pub value: u64,
+}
+
+/// The coins a campaign uses, largest first. Exactly one of them is worth 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Denomination>", into = "Vec<Denomination>")]
+pub struct Denominations(Vec<Denomination>);
+
+impl Default for Denominations {
+    fn default() -> Self {
+        Self(vec![
+            Denomination {
+                name: "pp".into(),
+                value: 1000,
+            },
+            Denomination {
+                name: "gp".into(),
+                value: 100,
+            },
+            Denomination {
+                name: "sp".into(),
+                value: 10,
+            },
+            Denomination {
+                name: "cp".into(),
+                value: 1,
+            },
+        ])
+    }
+}
+
+impl TryFrom<Vec<Denomination>> for Denominations {
+    type Error = MoneyError;
+
+    fn try_from(mut denominations: Vec<Denomination>) -> Result<Self, Self::Error> {
+        denominations.sort_by_key(|denomination| std::cmp::Reverse(denomination.value));
+
+        for (i, denomination) in denominations.iter().enumerate() {
+            let duplicate = denominations[..i].iter().any(|other| {
+                other.value == denomination.value
+                    || other.name.eq_ignore_ascii_case(&denomination.name)
+            });
+            if duplicate {
+                return Err(MoneyError::DuplicateDenomination(denomination.name.clone()));
+            }
+        }
+
+        if denominations.last().map(|d| d.value) != Some(1) {
+            return Err(MoneyError::MissingBaseUnit);
+        }
+
+        Ok(Self(denominations))
+    }
+}
+
+impl From<Denominations> for Vec<Denomination> {
+    fn from(denominations: Denominations) -> Self {
+        denominations.0
+    }
+}
+
+impl Denominations {
+    /// Writes `money` as mixed coins, i.e. "3 gp 4 sp 7 cp".
+    pub fn format(&self, money: Money) -> String {
+        let mut remaining = money.0;
+        let mut parts = Vec::new();
+
+        for denomination in &self.0 {
+            let count = remaining / denomination.value;
+            remaining %= denomination.value;
+            if count > 0 {
+                parts.push(format!("{count} {}", denomination.name));
+            }
+        }
+
+        match parts.is_empty() {
+            true => format!("0 {}", self.base_unit()),
+            false => parts.join(" "),
+        }
+    }
+
+    /// Reads amounts like "12gp 5sp" or "3 gp 7". Numbers without a denomination are
+    /// counted in the smallest unit.
+    pub fn parse(&self, input: &str) -> Result<Money, MoneyError> {
+        let mut chars = input.trim().chars().peekable();
+        let mut total: u64 = 0;
+        let mut parsed_any = false;
+
+        while chars.peek().is_some() {
+            let digits: String =
+                std::iter::from_fn(|| chars.next_if(char::is_ascii_digit)).collect();
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            let unit: String =
+                std::iter::from_fn(|| chars.next_if(char::is_ascii_alphabetic)).collect();
+            while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+
+            if digits.is_empty() {
+                return Err(MoneyError::InvalidFormat);
+            }
+            let count: u64 = digits.parse().map_err(|_| MoneyError::Overflow)?;
+            let value = match unit.as_str() {
+                "" => 1,
+                unit => self.value_of(unit)?,
+            };
+
+            total = count
+                .checked_mul(value)
+                .and_then(|amount| total.checked_add(amount))
+                .ok_or(MoneyError::Overflow)?;
+            parsed_any = true;
+        }
+
+        match parsed_any {
+            true => Ok(Money(total)),
+            false => Err(MoneyError::InvalidFormat),
+        }
+    }
+
+    fn value_of(&self, unit: &str) -> Result<u64, MoneyError> {
+        self.0
+            .iter()
+            .find(|denomination| denomination.name.eq_ignore_ascii_case(unit))
+            .map(|denomination| denomination.value)
+            .ok_or_else(|| MoneyError::UnknownDenomination(unit.to_string()))
+    }
+
+    fn base_unit(&self) -> &str {
+        self.0.last().map_or("", |denomination| &denomination.name)
+    }
+}
+
+impl FromStr for Denominations {
+    type Err = MoneyError;
+
+    /// Reads a table like "pp=1000, gp=100, sp=10, cp=1".
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input
+            .split(',')
+            .map(|entry| {
+                let (name, value) = entry.split_once('=').ok_or(MoneyError::InvalidFormat)?;
+                let name = name.trim();
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return Err(MoneyError::InvalidFormat);
+                }
+                let value = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| MoneyError::InvalidFormat)?;
+                if value == 0 {
+                    return Err(MoneyError::InvalidFormat);
+                }
+                Ok(Denomination {
+                    name: name.to_string(),
+                    value,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+    }
+}
+
+impl fmt::Display for Denominations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries: Vec<String> = self
+            .0
+            .iter()
+            .map(|denomination| format!("{}={}", denomination.name, denomination.value))
+            .collect();
+        write!(f, "{}", entries.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_mixed_denominations() {
+        let coins = Denominations::default();
+        assert_eq!(coins.format(Money(347)), "3 gp 4 sp 7 cp");
+        assert_eq!(coins.format(Money(1205)), "1 pp 2 gp 5 cp");
+        assert_eq!(coins.format(Money(0)), "0 cp");
+    }
+
+    #[test]
+    fn parses_mixed_denominations() {
+        let coins = Denominations::default();
+        assert_eq!(coins.parse("12gp 5sp"), Ok(Money(1250)));
+        assert_eq!(coins.parse("12 GP, 5 sp 3"), Ok(Money(1253)));
+        assert_eq!(coins.parse("40"), Ok(Money(40)));
+    }
+
+    #[test]
+    fn parse_rejects_bad_input() {
+        let coins = Denominations::default();
+        assert_eq!(coins.parse(""), Err(MoneyError::InvalidFormat));
+        assert_eq!(coins.parse("gp"), Err(MoneyError::InvalidFormat));
+        assert_eq!(
+            coins.parse("3 ducats"),
+            Err(MoneyError::UnknownDenomination("ducats".into()))
+        );
+        assert_eq!(
+            coins.parse("99999999999999999 pp"),
+            Err(MoneyError::Overflow)
+        );
+    }
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        let coins = Denominations::default();
+        for value in [0, 1, 9, 10, 99, 347, 1000, 123_456] {
+            assert_eq!(coins.parse(&coins.format(Money(value))), Ok(Money(value)));
+        }
+    }
+
+    #[test]
+    fn custom_denomination_table() {
+        let coins: Denominations = "crown=240, shilling=12, penny=1".parse().unwrap();
+        assert_eq!(coins.format(Money(253)), "1 crown 1 shilling 1 penny");
+        assert_eq!(coins.to_string(), "crown=240, shilling=12, penny=1");
+    }
+
+    #[test]
+    fn denomination_table_needs_a_base_unit() {
+        assert_eq!(
+            "gp=100, sp=10".parse::<Denominations>(),
+            Err(MoneyError::MissingBaseUnit)
+        );
+        assert_eq!(
+            "gp=100, GP=10, cp=1".parse::<Denominations>(),
+            Err(MoneyError::DuplicateDenomination("GP".into()))
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::fence::{Tenths, parse_tenths};
+use super::money::{Denominations, Money};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    pub name: String,
+    pub shares: u32,
+}
+
+/// A fixed amount taken off the top of every split, i.e. a bribe or a ship's passage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fee {
+    pub name: String,
+    pub amount: Money,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum FeeError {
+    InvalidFormat,
+}
+
+impl fmt::Display for FeeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeeError::InvalidFormat => write!(f, "Use Bribe: 5gp, Passage: 2gp 5sp"),
+        }
+    }
+}
+
+impl Fee {
+    /// Reads a list like "Bribe: 5gp, Passage: 2gp 5sp", with amounts in the campaign's coins.
+    pub fn parse_list(input: &str, coins: &Denominations) -> Result<Vec<Fee>, FeeError> {
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|fee| !fee.is_empty())
+            .map(|fee| {
+                let (name, amount) = fee.split_once(':')?;
+                let name = name.trim();
+                (!name.is_empty()).then_some(())?;
+                Some(Fee {
+                    name: name.to_string(),
+                    amount: coins.parse(amount).ok()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(FeeError::InvalidFormat)
+    }
+
+    pub fn format_list(fees: &[Fee], coins: &Denominations) -> String {
+        fees.iter()
+            .map(|fee| format!("{}: {}", fee.name, coins.format(fee.amount)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum GuildCutError {
+    InvalidFormat,
+    OverWhole,
+}
+
+impl fmt::Display for GuildCutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuildCutError::InvalidFormat => write!(f, "Use a percentage, i.e. 10%"),
+            GuildCutError::OverWhole => write!(f, "The guild can't take more than 100%"),
+        }
+    }
+}
+
+/// Reads a guild cut like "10%" or "2.5" into tenths of a percent. Blank means no cut.
+pub fn parse_guild_cut(input: &str) -> Result<u64, GuildCutError> {
+    if input.trim().is_empty() {
+        return Ok(0);
+    }
+    let cut = parse_tenths(input).ok_or(GuildCutError::InvalidFormat)?;
+    match cut > 1000 {
+        true => Err(GuildCutError::OverWhole),
+        false => Ok(cut),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SplitError {
+    NoMembers,
+    NoShares,
+    FeesExceedTotal { fees: Money, total: Money },
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitError::NoMembers => write!(f, "Add party members to split with"),
+            SplitError::NoShares => write!(f, "Give at least one member a share"),
+            SplitError::FeesExceedTotal { .. } => {
+                write!(f, "The fees come to more than the amount being split")
+            }
+        }
+    }
+}
+
+/// One member's cut of a split.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Share {
+    pub name: String,
+    pub amount: Money,
+    #[serde(default)]
+    pub paid: bool,
+}
+
+/// Where the money from one sale went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Payout {
+    pub total: Money,
+    pub fees: Vec<Fee>,
+    pub guild: Money,
+    pub shares: Vec<Share>,
+}
+
+/// A payout kept for the party's books.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitRecord {
+    pub day: u32,
+    pub session: u32,
+    /// What was split, i.e. "Sale to Dave".
+    pub source: String,
+    pub payout: Payout,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Party {
+    pub members: Vec<Member>,
+    /// Taken after fees, in tenths of a percent.
+    pub guild_cut: u64,
+    pub fees: Vec<Fee>,
+    pub splits: Vec<SplitRecord>,
+}
+
+impl Party {
+    /// Splits `total` after fees and the guild's cut, by shares. Coins that don't divide
+    /// evenly go one each to the members closest to earning them; ties go round the party
+    /// from split to split, so nobody always ends up with the odd copper.
+    pub fn split(&self, total: impl Into<Money>) -> Result<Payout, SplitError> {
+        let total = total.into();
+        if self.members.is_empty() {
+            return Err(SplitError::NoMembers);
+        }
+        let total_shares: u64 = self.members.iter().map(|m| u64::from(m.shares)).sum();
+        if total_shares == 0 {
+            return Err(SplitError::NoShares);
+        }
+        let fees = self
+            .fees
+            .iter()
+            .fold(0u64, |sum, fee| sum.saturating_add(fee.amount.0));
+        let after_fees = total
+            .0
+            .checked_sub(fees)
+            .ok_or(SplitError::FeesExceedTotal {
+                fees: Money(fees),
+                total,
+            })?;
+
+        let cut = u128::from(self.guild_cut.min(1000));
+        let guild = (u128::from(after_fees) * cut / 1000) as u64;
+        let pool = u128::from(after_fees - guild);
+        let exact: Vec<(u64, u128)> = self
+            .members
+            .iter()
+            .map(|member| {
+                let owed = pool * u128::from(member.shares);
+                let total_shares = u128::from(total_shares);
+                ((owed / total_shares) as u64, owed % total_shares)
+            })
+            .collect();
+        let handed_out: u64 = exact.iter().map(|(amount, _)| amount).sum();
+        let leftover = (pool as u64 - handed_out) as usize;
+
+        let count = self.members.len();
+        let turn = self.splits.len() % count;
+        let mut order: Vec<usize> = (0..count).collect();
+        order.sort_by_key(|&i| (std::cmp::Reverse(exact[i].1), (i + count - turn) % count));
+        let mut amounts: Vec<u64> = exact.iter().map(|(amount, _)| *amount).collect();
+        for &i in order.iter().take(leftover) {
+            amounts[i] += 1;
+        }
+
+        Ok(Payout {
+            total,
+            fees: self.fees.clone(),
+            guild: Money(guild),
+            shares: self
+                .members
+                .iter()
+                .zip(amounts)
+                .map(|(member, amount)| Share {
+                    name: member.name.clone(),
+                    amount: Money(amount),
+                    paid: false,
+                })
+                .collect(),
+        })
+    }
+
+    pub fn record(&mut self, split: SplitRecord) {
+        self.splits.push(split);
+    }
+
+    /// What each member is still owed from recorded splits, by name.
+    pub fn owed(&self) -> BTreeMap<String, Money> {
+        let mut owed: BTreeMap<String, Money> = BTreeMap::new();
+        for share in self.splits.iter().flat_map(|split| &split.payout.shares) {
+            let sum = owed.entry(share.name.clone()).or_default();
+            if !share.paid {
+                sum.0 = sum.0.saturating_add(share.amount.0);
+            }
+        }
+        owed
+    }
+
+    pub fn describe_cut(&self) -> String {
+        format!("{}%", Tenths(self.guild_cut))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn party(shares: &[u32]) -> Party {
+        Party {
+            members: shares
+                .iter()
+                .enumerate()
+                .map(|(i, shares)| Member {
+                    name: format!("Member {i}"),
+                    shares: *shares,
+                })
+                .collect(),
+            ..Party::default()
+        }
+    }
+
+    fn amounts(payout: &Payout) -> Vec<u64> {
+        payout.shares.iter().map(|share| share.amount.0).collect()
+    }
+
+    #[test]
+    fn fees_and_guild_come_off_the_top() {
+        let party = Party {
+            guild_cut: 100,
+            fees: vec![Fee {
+                name: "Bribe".into(),
+                amount: Money(100),
+            }],
+            ..party(&[1, 1])
+        };
+
+        let payout = party.split(1_100u64).unwrap();
+
+        assert_eq!(payout.guild, Money(100));
+        assert_eq!(amounts(&payout), vec![450, 450]);
+    }
+
+    #[test]
+    fn shares_weight_the_split_and_every_coin_is_handed_out() {
+        let payout = party(&[2, 1, 1]).split(Money(1_001)).unwrap();
+        assert_eq!(amounts(&payout), vec![501, 250, 250]);
+
+        let payout = party(&[1, 1, 1]).split(Money(100)).unwrap();
+        assert_eq!(amounts(&payout).iter().sum::<u64>(), 100);
+    }
+
+    #[test]
+    fn odd_coins_rotate_between_splits() {
+        let mut party = party(&[1, 1, 1]);
+        let mut extra = Vec::new();
+        for day in 0..3 {
+            let payout = party.split(Money(4)).unwrap();
+            extra.push(amounts(&payout).iter().position(|amount| *amount == 2));
+            party.record(SplitRecord {
+                day,
+                session: 1,
+                source: "Sale".into(),
+                payout,
+            });
+        }
+        assert_eq!(extra, vec![Some(0), Some(1), Some(2)]);
+        assert_eq!(party.owed()["Member 1"], Money(4));
+    }
+
+    #[test]
+    fn paid_shares_are_no_longer_owed() {
+        let mut party = party(&[1, 1]);
+        let mut payout = party.split(Money(100)).unwrap();
+        payout.shares[0].paid = true;
+        party.record(SplitRecord {
+            day: 1,
+            session: 1,
+            source: "Sale".into(),
+            payout,
+        });
+
+        let owed = party.owed();
+        assert_eq!(owed["Member 0"], Money(0));
+        assert_eq!(owed["Member 1"], Money(50));
+    }
+
+    #[test]
+    fn split_reports_what_cannot_be_done() {
+        assert_eq!(
+            Party::default().split(Money(10)),
+            Err(SplitError::NoMembers)
+        );
+        assert_eq!(party(&[0]).split(Money(10)), Err(SplitError::NoShares));
+        let party = Party {
+            fees: vec![Fee {
+                name: "Passage".into(),
+                amount: Money(50),
+            }],
+            ..party(&[1])
+        };
+        assert_eq!(
+            party.split(Money(10)),
+            Err(SplitError::FeesExceedTotal {
+                fees: Money(50),
+                total: Money(10)
+            })
+        );
+    }
+
+    #[test]
+    fn guild_cut_parses_as_a_percentage() {
+        assert_eq!(parse_guild_cut("10%"), Ok(100));
+        assert_eq!(parse_guild_cut("2.5"), Ok(25));
+        assert_eq!(parse_guild_cut(" "), Ok(0));
+        assert_eq!(parse_guild_cut("150%"), Err(GuildCutError::OverWhole));
+        assert_eq!(
+            parse_guild_cut("a tithe"),
+            Err(GuildCutError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn fees_parse_and_format() {
+        let coins = Denominations::default();
+        let fees = Fee::parse_list("Bribe: 5gp, Passage: 2gp 5sp", &coins).unwrap();
+        assert_eq!(fees[1].amount, Money(250));
+        assert_eq!(
+            Fee::format_list(&fees, &coins),
+            "Bribe: 5 gp, Passage: 2 gp 5 sp"
+        );
+        assert_eq!(
+            Fee::parse_list("Bribe 5gp", &coins),
+            Err(FeeError::InvalidFormat)
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::fence::Tenths;
+
+/// A standing change to every price in a region or city, in tenths of a percent. Positive
+/// modifiers go in the fence's favour, i.e. "Crackdown +10%", negative ones in the party's,
+/// i.e. "Port discount -5%".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceModifier {
+    pub name: String,
+    pub points: i64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PriceModifierError {
+    MissingName,
+    InvalidFormat,
+    OutOfRange { max: i64 },
+}
+
+impl fmt::Display for PriceModifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriceModifierError::MissingName => write!(f, "Give the modifier a name"),
+            PriceModifierError::InvalidFormat => {
+                write!(f, "Use a name and a percentage, i.e. Port discount -5%")
+            }
+            PriceModifierError::OutOfRange { max } => {
+                write!(
+                    f,
+                    "Modifiers must be within ±{}%",
+                    Tenths(max.unsigned_abs())
+                )
+            }
+        }
+    }
+}
+
+impl PriceModifier {
+    /// The furthest a single modifier can move prices, in tenths of a percent.
+    pub const MAX_POINTS: i64 = 1000;
+
+    /// Reads a list like "Port discount -5%, Crackdown +10%".
+    pub fn parse_list(input: &str) -> Result<Vec<PriceModifier>, PriceModifierError> {
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|modifier| !modifier.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+
+    pub fn format_list(modifiers: &[PriceModifier]) -> String {
+        modifiers
+            .iter()
+            .map(PriceModifier::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl FromStr for PriceModifier {
+    type Err = PriceModifierError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (name, amount) = input
+            .trim()
+            .rsplit_once(' ')
+            .ok_or(PriceModifierError::InvalidFormat)?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(PriceModifierError::MissingName);
+        }
+        let amount: f64 = amount
+            .trim_end_matches('%')
+            .parse()
+            .map_err(|_| PriceModifierError::InvalidFormat)?;
+        if !amount.is_finite() {
+            return Err(PriceModifierError::InvalidFormat);
+        }
+        let points = (amount * 10.0).round();
+        if points.abs() > Self::MAX_POINTS as f64 {
+            return Err(PriceModifierError::OutOfRange {
+                max: Self::MAX_POINTS,
+            });
+        }
+
+        Ok(PriceModifier {
+            name: name.to_string(),
+            points: points as i64,
+        })
+    }
+}
+
+impl fmt::Display for PriceModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.points < 0 { '-' } else { '+' };
+        write!(
+            f,
+            "{} {sign}{}%",
+            self.name,
+            Tenths(self.points.unsigned_abs())
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct City {
+    pub name: String,
+    pub modifiers: Vec<PriceModifier>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub name: String,
+    /// Apply in every city of the region.
+    pub modifiers: Vec<PriceModifier>,
+    pub cities: Vec<City>,
+}
+
+/// Where a fence works, by region and city name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub region: String,
+    pub city: String,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.city, self.region)
+    }
+}
+
+/// The modifiers on prices in one place. The default is a market with none.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Market {
+    pub modifiers: Vec<PriceModifier>,
+}
+
+impl Market {
+    /// All the modifiers together, in tenths of a percent.
+    pub fn points(&self) -> i64 {
+        self.modifiers
+            .iter()
+            .fold(0, |sum: i64, modifier| sum.saturating_add(modifier.points))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Regions(pub Vec<Region>);
+
+impl Default for Regions {
+    fn default() -> Self {
+        Self(vec![Region {
+            name: "Free Cities".into(),
+            modifiers: Vec::new(),
+            cities: vec![
+                City {
+                    name: "Saltmarsh".into(),
+                    modifiers: vec![PriceModifier {
+                        name: "Port discount".into(),
+                        points: -50,
+                    }],
+                },
+                City {
+                    name: "Greyhollow".into(),
+                    modifiers: vec![PriceModifier {
+                        name: "Crackdown".into(),
+                        points: 100,
+                    }],
+                },
+            ],
+        }])
+    }
+}
+
+impl Regions {
+    pub fn region(&self, name: &str) -> Option<&Region> {
+        self.0.iter().find(|region| region.name == name)
+    }
+
+    /// The market at `location`. Places that no longer exist have no modifiers.
+    pub fn market(&self, location: Option<&Location>) -> Market {
+        let Some(location) = location else {
+            return Market::default();
+        };
+        let Some(region) = self.region(&location.region) else {
+            return Market::default();
+        };
+        let city = region.cities.iter().find(|city| city.name == location.city);
+
+        Market {
+            modifiers: region
+                .modifiers
+                .iter()
+                .chain(city.into_iter().flat_map(|city| &city.modifiers))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Sets the modifiers of a region, or of one of its cities, adding either if it is new.
+    pub fn set_modifiers(
+        &mut self,
+        region: &str,
+        city: Option<&str>,
+        modifiers: Vec<PriceModifier>,
+    ) {
+        let index = match self.0.iter().position(|known| known.name == region) {
+            Some(index) => index,
+            None => {
+                self.0.push(Region {
+                    name: region.to_string(),
+                    modifiers: Vec::new(),
+                    cities: Vec::new(),
+                });
+                self.0.len() - 1
+            }
+        };
+        let region = &mut self.0[index];
+        let Some(city) = city else {
+            region.modifiers = modifiers;
+            return;
+        };
+        match region.cities.iter_mut().find(|known| known.name == city) {
+            Some(known) => known.modifiers = modifiers,
+            None => region.cities.push(City {
+                name: city.to_string(),
+                modifiers,
+            }),
+        }
+    }
+
+    /// Removes a whole region, or just one of its cities.
+    pub fn remove(&mut self, region: &str, city: Option<&str>) {
+        match city {
+            Some(city) => {
+                if let Some(region) = self.0.iter_mut().find(|known| known.name == region) {
+                    region.cities.retain(|known| known.name != city);
+                }
+            }
+            None => self.0.retain(|known| known.name != region),
+        }
+    }
+
+    /// Every city as a location, region by region.
+    pub fn locations(&self) -> Vec<Location> {
+        self.0
+            .iter()
+            .flat_map(|region| {
+                region.cities.iter().map(|city| Location {
+                    region: region.name.clone(),
+                    city: city.name.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_parse_and_format() {
+        let modifiers = PriceModifier::parse_list("Port discount -5%, Crackdown +10, Toll 2.5%");
+        assert_eq!(
+            modifiers.as_ref().map(|modifiers| modifiers
+                .iter()
+                .map(|modifier| modifier.points)
+                .collect::<Vec<_>>()),
+            Ok(vec![-50, 100, 25])
+        );
+        assert_eq!(
+            PriceModifier::format_list(&modifiers.unwrap()),
+            "Port discount -5%, Crackdown +10%, Toll +2.5%"
+        );
+        assert_eq!(
+            "-5%".parse::<PriceModifier>(),
+            Err(PriceModifierError::InvalidFormat)
+        );
+        assert_eq!(
+            "Toll lots".parse::<PriceModifier>(),
+            Err(PriceModifierError::InvalidFormat)
+        );
+        assert_eq!(
+            "Gems 1e30%".parse::<PriceModifier>(),
+            Err(PriceModifierError::OutOfRange { max: 1000 })
+        );
+        assert_eq!("Gems -100%".parse::<PriceModifier>().unwrap().points, -1000);
+    }
+
+    #[test]
+    fn huge_saved_modifiers_saturate() {
+        let huge = |points| PriceModifier {
+            name: "Huge".into(),
+            points,
+        };
+        let market = Market {
+            modifiers: vec![huge(i64::MAX), huge(i64::MAX)],
+        };
+        assert_eq!(market.points(), i64::MAX);
+    }
+
+    #[test]
+    fn market_adds_region_and_city_modifiers() {
+        let mut regions = Regions::default();
+        regions.0[0].modifiers.push(PriceModifier {
+            name: "War tax".into(),
+            points: 20,
+        });
+        let location = |city: &str| Location {
+            region: "Free Cities".into(),
+            city: city.into(),
+        };
+
+        assert_eq!(regions.market(Some(&location("Saltmarsh"))).points(), -30);
+        assert_eq!(regions.market(Some(&location("Greyhollow"))).points(), 120);
+        assert_eq!(regions.market(Some(&location("Atlantis"))).points(), 20);
+        assert_eq!(regions.market(None), Market::default());
+    }
+
+    #[test]
+    fn regions_and_cities_are_added_updated_and_removed() {
+        let mut regions = Regions(Vec::new());
+        let toll = |points| {
+            vec![PriceModifier {
+                name: "Toll".into(),
+                points,
+            }]
+        };
+        regions.set_modifiers("Marches", Some("Fort Hale"), toll(10));
+        regions.set_modifiers("Marches", None, toll(5));
+        regions.set_modifiers("Marches", Some("Fort Hale"), toll(20));
+
+        let fort = Location {
+            region: "Marches".into(),
+            city: "Fort Hale".into(),
+        };
+        assert_eq!(regions.locations(), vec![fort.clone()]);
+        assert_eq!(regions.market(Some(&fort)).points(), 25);
+
+        regions.remove("Marches", Some("Fort Hale"));
+        assert!(regions.locations().is_empty());
+        regions.remove("Marches", None);
+        assert_eq!(regions, Regions(Vec::new()));
+    }
+}
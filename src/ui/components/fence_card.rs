@@ -1,47 +1,167 @@
-use crate::model::fence::Fence;
+use crate::model::catalog::{Category, LootBag};
+use crate::model::fence::bulk::BulkQuote;
+use crate::model::fence::saturation::{DROP_PER_UNIT, MAX_DROP};
+use crate::model::fence::{Fence, LootLine, PriceBand, Side, Spread, Tenths};
+use crate::model::money::{Denominations, Money};
+use crate::model::region::{Market, PriceModifier, Regions};
 use crate::ui::components::layout::vert_stack;
 use crate::ui::screens::fence::FenceMessage;
-use iced::widget::{Container, container};
+use iced::widget::{Column, Container, container};
 use iced::{Background, Border, Color, Length, Theme};
 use iced::{
     Element,
     widget::{Row, Text, button},
 };
 
+/// What the Fence screen is currently asking every fence to price.
+pub struct QuoteInputs<'a> {
+    pub base_price: Option<Money>,
+    pub error: &'a Option<String>,
+    pub loot: &'a LootBag,
+    pub coins: &'a Denominations,
+    /// The in-game day, which decides how hot the loot still is.
+    pub day: u32,
+    pub regions: &'a Regions,
+}
+
+/// Panels the screen has open under this card. Their buttons close them again.
+pub struct CardPanels<'a> {
+    pub haggle: Option<Element<'a, FenceMessage>>,
+    pub ledger: Option<Element<'a, FenceMessage>>,
+    pub roll: Option<Element<'a, FenceMessage>>,
+}
+
 pub fn fence_card<'a>(
+    index: usize,
     fence: &Fence,
-    base_price: Option<u64>,
-    error: &'a Option<String>,
-    on_edit: FenceMessage,
+    inputs: &QuoteInputs<'a>,
+    panels: CardPanels<'a>,
 ) -> Element<'a, FenceMessage> {
-    let computed_prices = base_price.map(|base| {
-        (
-            fence.lowest_markup_price(base),
-            fence.avg_markup_price(base),
-            fence.highest_markup_price(base),
-        )
+    let QuoteInputs {
+        base_price,
+        error,
+        loot,
+        coins,
+        day,
+        regions,
+    } = *inputs;
+    let market = regions.market(fence.location.as_ref());
+    let side_rows = [Side::Buy, Side::Sell].map(|side| {
+        let prices = match base_price.map(|base| fence.price_band(side, base.0, &market)) {
+            Some(Ok(prices)) => format!("{side}: {}", band(&prices, coins)),
+            Some(Err(err)) => err.to_string(),
+            None => match error {
+                Some(error) => error.clone(),
+                None => "Enter a valid base price".into(),
+            },
+        };
+        vert_stack()
+            .spacing(4)
+            .push(Text::new(format!(
+                "{side} {} (at reputation {}: {})",
+                spread(&fence.spread(side)),
+                fence.reputation,
+                spread(&fence.adjusted_spread(side))
+            )))
+            .push(Text::new(prices))
     });
+    let loot_quote = (!loot.is_empty()).then(|| {
+        match (
+            fence.quote_loot(Side::Buy, loot, day, &market),
+            fence.quote_loot(Side::Sell, loot, day, &market),
+        ) {
+            (Ok(pays), Ok(charges)) => pays
+                .lines
+                .iter()
+                .map(|line| loot_line(fence, line, coins))
+                .fold(vert_stack().spacing(4), |col, line| {
+                    col.push(Text::new(line))
+                })
+                .push(Text::new(format!(
+                    "Loot total: pays {}, charges {}",
+                    band(&pays.total, coins),
+                    band(&charges.total, coins)
+                ))),
+            (Err(err), _) | (_, Err(err)) => vert_stack().push(Text::new(err.to_string())),
+        }
+    });
+    let bulk_quote = (!loot.is_empty()).then(|| bulk_sale(fence, &market, loot, day, coins));
+    let [buy_row, sell_row] = side_rows;
+    let on_haggle = match panels.haggle {
+        Some(_) => FenceMessage::CloseHaggle,
+        None => FenceMessage::OpenHaggle(index),
+    };
+    let on_ledger = match panels.ledger {
+        Some(_) => FenceMessage::CloseLedger,
+        None => FenceMessage::OpenLedger(index),
+    };
+    let on_roll = match panels.roll {
+        Some(_) => FenceMessage::CloseRoll,
+        None => FenceMessage::OpenRoll(index),
+    };
     Container::new(
         vert_stack()
-            .push(Row::new().push(button("Edit").on_press(on_edit)))
             .push(
                 Row::new()
-                    .spacing(20)
-                    .push(Text::new(format!("Low markup: {}", fence.lowest_markup)))
-                    .push(Text::new(format!("Avg markup: {}", fence.avg_markup)))
-                    .push(Text::new(format!("High markup: {}", fence.highest_markup))),
+                    .spacing(10)
+                    .push(Text::new(fence.name.clone()).size(20).width(Length::Fill))
+                    .push(button("Up").on_press(FenceMessage::MoveUp(index)))
+                    .push(button("Down").on_press(FenceMessage::MoveDown(index)))
+                    .push(button("Duplicate").on_press(FenceMessage::Duplicate(index)))
+                    .push(button("Delete").on_press(FenceMessage::Delete(index))),
             )
-            .push(if let Some((low, avg, high)) = computed_prices {
+            .push(
                 Row::new()
-                    .spacing(20)
-                    .push(Text::new(format!("Low: {}", low)))
-                    .push(Text::new(format!("Avg: {}", avg)))
-                    .push(Text::new(format!("High: {}", high)))
-            } else if let Some(error) = error {
-                Row::new().push(Text::new(error))
-            } else {
-                Row::new().push(Text::new("Enter a valid base price"))
-            }),
+                    .spacing(10)
+                    .push(button("Edit").on_press(FenceMessage::Edit(index)))
+                    .push(button("Roll price").on_press(on_roll))
+                    .push(button("Haggle").on_press(on_haggle))
+                    .push(button("Ledger").on_press(on_ledger))
+                    .push(button("Split quote").on_press(FenceMessage::SplitQuote(index))),
+            )
+            .push(Text::new(location(fence, &market)))
+            .push(Text::new(personality(fence)))
+            .push((!fence.specialties.is_empty()).then(|| {
+                Text::new(format!(
+                    "\u{2605} Specialises in {}",
+                    categories(&fence.specialties)
+                ))
+                .color(Color::from_rgb8(224, 180, 92))
+            }))
+            .push(
+                (!fence.refused.is_empty())
+                    .then(|| Text::new(format!("Won't deal in {}", categories(&fence.refused)))),
+            )
+            .push(Text::new(format!(
+                "Reputation curve: {}, rounding: {}",
+                fence.reputation_curve, fence.rounding
+            )))
+            .push(Text::new(format!(
+                "Heat: surcharges over {} ({}), refuses over {}",
+                fence.heat_tolerance.comfort, fence.heat_tolerance.risk, fence.heat_tolerance.limit
+            )))
+            .push(Text::new(format!(
+                "Volume discounts: {}; {}",
+                match fence.volume_tiers.0.is_empty() {
+                    true => "none".into(),
+                    false => fence.volume_tiers.format(coins),
+                },
+                match fence.cash_left(day) {
+                    Some(left) => format!(
+                        "pays out up to {} a week, {} left",
+                        coins.format(fence.weekly_cash.unwrap_or_default()),
+                        coins.format(left)
+                    ),
+                    None => "no weekly cash limit".into(),
+                }
+            )))
+            .push(buy_row)
+            .push(sell_row)
+            .push(loot_quote)
+            .push(bulk_quote)
+            .push(panels.roll)
+            .push(panels.haggle)
+            .push(panels.ledger),
     )
     .style(|_theme: &Theme| container::Style {
         background: Some(Background::Color(Color::from_rgb8(48, 35, 28))),
@@ -57,3 +177,136 @@ pub fn fence_card<'a>(
     .width(Length::Fill)
     .into()
 }
+
+fn band(prices: &PriceBand, coins: &Denominations) -> String {
+    format!(
+        "{} / {} / {}",
+        coins.format(Money(prices.low)),
+        coins.format(Money(prices.avg)),
+        coins.format(Money(prices.high))
+    )
+}
+
+fn loot_line(fence: &Fence, line: &LootLine, coins: &Denominations) -> String {
+    let item = format!("{} x{} (heat {})", line.name, line.quantity, line.heat);
+    if let Some(refusal) = &line.refusal {
+        return format!("{item}: refuses, {refusal}");
+    }
+
+    let mut reasons = Vec::new();
+    if fence.specialties.contains(&line.category) {
+        reasons.push(format!(
+            "\u{2605} {} is a specialty, {}% better",
+            line.category,
+            Tenths(Fence::SPECIALTY_EDGE.unsigned_abs())
+        ));
+    }
+    if line.surcharge > 0 {
+        reasons.push(format!("{}% risk surcharge", Tenths(line.surcharge)));
+    }
+    if line.glut.0 > 0 {
+        reasons.push(format!(
+            "{}% off with {} like it still on its hands",
+            Tenths(line.glut.drop()),
+            line.glut.0
+        ));
+    }
+    if line.quantity > 1 && line.glut.drop() < MAX_DROP {
+        reasons.push(format!(
+            "each one it takes knocks {}% off the next",
+            Tenths(DROP_PER_UNIT)
+        ));
+    }
+    let prices = format!(
+        "{item}: pays {} each, {}",
+        band(&line.unit, coins),
+        band(&line.total, coins)
+    );
+    match reasons.is_empty() {
+        true => prices,
+        false => format!("{prices} ({})", reasons.join("; ")),
+    }
+}
+
+/// The loot the fence will touch, sold to it in one go.
+fn bulk_sale<'a>(
+    fence: &Fence,
+    market: &Market,
+    loot: &LootBag,
+    day: u32,
+    coins: &Denominations,
+) -> Column<'a, FenceMessage> {
+    let quote = match fence.bulk_quote(Side::Buy, loot, day, market) {
+        Ok(quote) => quote,
+        Err(err) => return vert_stack().push(Text::new(err.to_string())),
+    };
+
+    let BulkQuote {
+        lines,
+        total,
+        reduction,
+        deferred,
+        ..
+    } = &quote;
+    let heading = match reduction {
+        0 => "Bulk sale:".to_string(),
+        reduction => format!("Bulk sale, {}% volume discount:", Tenths(*reduction)),
+    };
+    let total = match deferred {
+        0 => format!("Bulk total: pays {}", band(total, coins)),
+        deferred => format!(
+            "Bulk total: pays {}, {} of it after the fence raises more coin",
+            band(total, coins),
+            coins.format(Money(*deferred))
+        ),
+    };
+    lines
+        .iter()
+        .map(|line| {
+            let item = format!(
+                "    {} x{} (worth {}): pays {}",
+                line.name,
+                line.quantity,
+                coins.format(line.base),
+                band(&line.price, coins)
+            );
+            match line.deferred {
+                0 => item,
+                deferred => format!("{item}, {} deferred", coins.format(Money(deferred))),
+            }
+        })
+        .fold(
+            vert_stack().spacing(4).push(Text::new(heading)),
+            |col, line| col.push(Text::new(line)),
+        )
+        .push(Text::new(total))
+}
+
+fn personality(fence: &Fence) -> String {
+    fence
+        .personality
+        .map_or("No particular personality".to_string(), |p| p.to_string())
+}
+
+fn categories(categories: &[Category]) -> String {
+    categories
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn location(fence: &Fence, market: &Market) -> String {
+    match (&fence.location, market.modifiers.is_empty()) {
+        (None, _) => "Works anywhere, no regional prices".into(),
+        (Some(location), true) => format!("Works in {location}"),
+        (Some(location), false) => format!(
+            "Works in {location}: {}",
+            PriceModifier::format_list(&market.modifiers)
+        ),
+    }
+}
+
+fn spread(spread: &Spread) -> String {
+    format!("{} / {} / {}", spread.lowest, spread.avg, spread.highest)
+}
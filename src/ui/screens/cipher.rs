@@ -1,6 +1,8 @@
+use std::fmt;
+
 use iced::{
     Element,
-    widget::{TextInput, button, row, text},
+    widget::{Column, Row, TextInput, button, container, pick_list, row, text},
 };
 
 use crate::ui::components::{card::card, layout::vert_stack};
@@ -8,16 +10,249 @@ use crate::ui::components::{card::card, layout::vert_stack};
 use crate::{
     app::AppScreen,
     model::cipher::{
-        caesar_cipher::Caesar, cipher_traits::CipherTraits, vigenere_cipher::Vigenere,
+        affine_cipher::Affine,
+        atbash_cipher::Atbash,
+        caesar_cipher::Caesar,
+        cipher_traits::CipherTraits,
+        columnar_cipher::{Columnar, DoubleColumnar},
+        key_square::KeySquare,
+        playfair_cipher::Playfair,
+        rail_fence_cipher::RailFence,
+        rotor_machine::{RotorError, RotorMachine},
+        square_cipher::{FourSquare, TwoSquare},
+        substitution_cipher::Substitution,
+        vigenere_cipher::{Vigenere, VigenereMode},
     },
 };
 
+/// The ciphers picked from a list, each set up from the one key input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CipherKind {
+    #[default]
+    Atbash,
+    Affine,
+    Substitution,
+    RailFence,
+    Columnar,
+    DoubleColumnar,
+    Playfair,
+    TwoSquare,
+    FourSquare,
+    RotorMachine,
+}
+
+impl CipherKind {
+    const ALL: [CipherKind; 10] = [
+        CipherKind::Atbash,
+        CipherKind::Affine,
+        CipherKind::Substitution,
+        CipherKind::RailFence,
+        CipherKind::Columnar,
+        CipherKind::DoubleColumnar,
+        CipherKind::Playfair,
+        CipherKind::TwoSquare,
+        CipherKind::FourSquare,
+        CipherKind::RotorMachine,
+    ];
+
+    fn key_hint(self) -> &'static str {
+        match self {
+            CipherKind::Atbash => "no key needed",
+            CipherKind::Affine => "a and b, i.e. 5 8",
+            CipherKind::Substitution => "keyword or a full mixed alphabet",
+            CipherKind::RailFence => "rails and offset, i.e. 3 0",
+            CipherKind::Columnar => "keyword",
+            CipherKind::DoubleColumnar => "two keywords, i.e. ZEBRA STRIPE",
+            CipherKind::Playfair => "keyword and a filler letter, i.e. MONARCHY X",
+            CipherKind::TwoSquare | CipherKind::FourSquare => {
+                "two keywords and a filler letter, i.e. EXAMPLE KEYWORD X"
+            }
+            CipherKind::RotorMachine => {
+                "rotors and reflector, then ring=, pos= and plugs=, i.e. I II III B pos=ADU plugs=AB,CD"
+            }
+        }
+    }
+
+    /// The key squares to print for the square ciphers, laid out row by row.
+    fn squares(self, key: &str) -> Vec<Vec<KeySquare>> {
+        let (keywords, filler) = square_key(key);
+        let keyword = |i: usize| keywords.get(i).copied().unwrap_or_default();
+        match self {
+            CipherKind::Playfair => Playfair::new(&keywords.join(" "), filler)
+                .map(|cipher| vec![vec![cipher.square]])
+                .unwrap_or_default(),
+            CipherKind::TwoSquare => TwoSquare::new(keyword(0), keyword(1), filler)
+                .map(|cipher| vec![vec![cipher.top], vec![cipher.bottom]])
+                .unwrap_or_default(),
+            CipherKind::FourSquare => FourSquare::new(keyword(0), keyword(1), filler)
+                .map(|cipher| {
+                    cipher
+                        .squares()
+                        .iter()
+                        .map(|row| row.iter().map(|&square| square.clone()).collect())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn build(self, key: &str) -> Result<Box<dyn CipherTraits>, String> {
+        match self {
+            CipherKind::Atbash => Ok(Box::new(Atbash)),
+            CipherKind::Affine => {
+                let [a, b] = numbers(key)
+                    .as_deref()
+                    .and_then(|numbers| numbers.try_into().ok())
+                    .ok_or("Enter a and b as whole numbers")?;
+                Ok(Box::new(Affine::new(a, b).map_err(|err| err.to_string())?))
+            }
+            CipherKind::Substitution => Ok(Box::new(
+                Substitution::keyed(key).map_err(|err| err.to_string())?,
+            )),
+            CipherKind::RailFence => {
+                let (rails, offset) = match numbers(key).as_deref() {
+                    Some(&[rails]) => (rails, 0),
+                    Some(&[rails, offset]) => (rails, offset),
+                    _ => return Err("Enter the rails and an offset as whole numbers".into()),
+                };
+                let rails = usize::try_from(rails).unwrap_or_default();
+                let offset = usize::try_from(offset).map_err(|_| "The offset can't be negative")?;
+                Ok(Box::new(
+                    RailFence::new(rails, offset).map_err(|err| err.to_string())?,
+                ))
+            }
+            CipherKind::Columnar => {
+                Ok(Box::new(Columnar::new(key).map_err(|err| err.to_string())?))
+            }
+            CipherKind::DoubleColumnar => {
+                let (first, second) = key
+                    .trim()
+                    .split_once([' ', ','])
+                    .ok_or("Enter two keywords")?;
+                Ok(Box::new(
+                    DoubleColumnar::new(first, second.trim()).map_err(|err| err.to_string())?,
+                ))
+            }
+            CipherKind::Playfair => {
+                let (keywords, filler) = square_key(key);
+                Ok(Box::new(
+                    Playfair::new(&keywords.join(" "), filler).map_err(|err| err.to_string())?,
+                ))
+            }
+            CipherKind::TwoSquare | CipherKind::FourSquare => {
+                let (keywords, filler) = square_key(key);
+                let [first, second] = keywords[..] else {
+                    return Err("Enter two keywords".into());
+                };
+                match self {
+                    CipherKind::TwoSquare => Ok(Box::new(
+                        TwoSquare::new(first, second, filler).map_err(|err| err.to_string())?,
+                    )),
+                    _ => Ok(Box::new(
+                        FourSquare::new(first, second, filler).map_err(|err| err.to_string())?,
+                    )),
+                }
+            }
+            CipherKind::RotorMachine => Ok(Box::new(rotor_machine(key)?)),
+        }
+    }
+}
+
+/// Keywords for a square cipher, and the filler if the key ends in a single character.
+/// The filler defaults to X.
+fn square_key(key: &str) -> (Vec<&str>, char) {
+    let mut words: Vec<&str> = key.split_whitespace().collect();
+    let filler = match words[..] {
+        [_, .., last] if last.chars().count() == 1 => last.chars().next(),
+        _ => None,
+    };
+    if filler.is_some() {
+        words.pop();
+    }
+    (words, filler.unwrap_or('X'))
+}
+
+fn rotor_machine(key: &str) -> Result<RotorMachine, String> {
+    key.parse().map_err(|err: RotorError| err.to_string())
+}
+
+/// The letters of `text` as the rotor machine takes them, each under the rotor positions it
+/// was enciphered at.
+fn stepping<'a>(kind: CipherKind, key: &str, text_to_encrypt: &str) -> Element<'a, CipherMessage> {
+    let steps = match kind {
+        CipherKind::RotorMachine => rotor_machine(key)
+            .map(|machine| machine.steps(text_to_encrypt))
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    Row::with_children(steps.into_iter().map(|step| {
+        Column::with_children([
+            text(step.positions).size(12).into(),
+            text(format!("{} → {}", step.input, step.output)).into(),
+        ])
+        .into()
+    }))
+    .spacing(12)
+    .wrap()
+    .into()
+}
+
+/// Key squares as a grid of letters, to print as a prop.
+fn grid<'a>(squares: Vec<Vec<KeySquare>>) -> Element<'a, CipherMessage> {
+    Column::with_children(squares.into_iter().map(|squares| {
+        Row::with_children(squares.into_iter().map(|square| {
+            container(Column::with_children(square.rows.map(|letters| {
+                Row::with_children(
+                    letters.map(|letter| text(letter.to_string()).size(20).width(24).into()),
+                )
+                .into()
+            })))
+            .padding(8)
+            .style(container::rounded_box)
+            .into()
+        }))
+        .spacing(8)
+        .into()
+    }))
+    .spacing(8)
+    .into()
+}
+
+/// Whole numbers split by spaces or commas, or `None` if any part isn't one.
+fn numbers(key: &str) -> Option<Vec<i16>> {
+    key.split([' ', ','])
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+impl fmt::Display for CipherKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherKind::Atbash => write!(f, "Atbash"),
+            CipherKind::Affine => write!(f, "Affine"),
+            CipherKind::Substitution => write!(f, "Keyword substitution"),
+            CipherKind::RailFence => write!(f, "Rail fence"),
+            CipherKind::Columnar => write!(f, "Columnar transposition"),
+            CipherKind::DoubleColumnar => write!(f, "Double columnar transposition"),
+            CipherKind::Playfair => write!(f, "Playfair"),
+            CipherKind::TwoSquare => write!(f, "Two-square"),
+            CipherKind::FourSquare => write!(f, "Four-square"),
+            CipherKind::RotorMachine => write!(f, "Rotor machine"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum CipherMessage {
     Increment,
     Decrement,
     ContentChanged(String),
+    VigenereMode(VigenereMode),
     InputChanged(String),
+    Kind(CipherKind),
+    Key(String),
 }
 
 #[derive(Default)]
@@ -26,11 +261,37 @@ pub struct CipherState {
     vigenere_cipher: Option<Vigenere>,
 
     vigenere_keyword: String,
+    vigenere_mode: VigenereMode,
 
     to_encrypt: String,
 
     caesar_encrypted: String,
     vigenere_encrypted: String,
+
+    kind: CipherKind,
+    key: String,
+    chosen_encrypted: String,
+    chosen_error: Option<String>,
+}
+
+impl CipherState {
+    fn encrypt_vigenere(&mut self) {
+        self.vigenere_cipher = Vigenere::with_mode(&self.vigenere_keyword, self.vigenere_mode).ok();
+        self.vigenere_encrypted = match &self.vigenere_cipher {
+            Some(vigenere) => vigenere.encrypt(&self.to_encrypt),
+            None => self.to_encrypt.clone(),
+        };
+    }
+
+    fn encrypt_chosen(&mut self) {
+        match self.kind.build(&self.key) {
+            Ok(cipher) => {
+                self.chosen_encrypted = cipher.encrypt(&self.to_encrypt);
+                self.chosen_error = None;
+            }
+            Err(err) => self.chosen_error = Some(err),
+        }
+    }
 }
 
 impl AppScreen for CipherState {
@@ -44,10 +305,18 @@ impl AppScreen for CipherState {
                     text(self.caesar_cipher.shift),
                     button("Decrement").on_press(CipherMessage::Decrement),
                 ])
-                .push(row![
-                    TextInput::new("keyword", &self.vigenere_keyword)
-                        .on_input(CipherMessage::ContentChanged)
-                ])
+                .push(
+                    row![
+                        TextInput::new("keyword", &self.vigenere_keyword)
+                            .on_input(CipherMessage::ContentChanged),
+                        pick_list(
+                            &VigenereMode::ALL[..],
+                            Some(self.vigenere_mode),
+                            CipherMessage::VigenereMode
+                        ),
+                    ]
+                    .spacing(10),
+                )
                 .push(row![
                     TextInput::new("text to encrypt", &self.to_encrypt)
                         .on_input(CipherMessage::InputChanged)
@@ -59,7 +328,29 @@ impl AppScreen for CipherState {
                 .push(row![
                     text("the vigenere encrypted string: "),
                     text(&self.vigenere_encrypted)
-                ]),
+                ])
+                .push(self.vigenere_cipher.as_ref().and_then(|vigenere| {
+                    (vigenere.mode == VigenereMode::RunningKey
+                        && !vigenere.key_covers(&self.to_encrypt))
+                    .then(|| text("the running key is shorter than the text, so it repeats"))
+                }))
+                .push(
+                    row![
+                        pick_list(&CipherKind::ALL[..], Some(self.kind), CipherMessage::Kind),
+                        TextInput::new(self.kind.key_hint(), &self.key)
+                            .on_input(CipherMessage::Key),
+                    ]
+                    .spacing(10),
+                )
+                .push(match &self.chosen_error {
+                    Some(err) => row![text(err)],
+                    None => row![
+                        text(format!("the {} encrypted string: ", self.kind)),
+                        text(&self.chosen_encrypted)
+                    ],
+                })
+                .push(grid(self.kind.squares(&self.key)))
+                .push(stepping(self.kind, &self.key, &self.to_encrypt)),
         )
     }
 
@@ -74,15 +365,12 @@ impl AppScreen for CipherState {
                 self.caesar_encrypted = Caesar::encrypt(&self.caesar_cipher, &self.to_encrypt)
             }
             CipherMessage::ContentChanged(keyword) => {
-                match Vigenere::new(&keyword) {
-                    Ok(v) => self.vigenere_cipher = Some(v),
-                    Err(_) => self.vigenere_cipher = None,
-                };
                 self.vigenere_keyword = keyword;
-                self.vigenere_encrypted = match &self.vigenere_cipher {
-                    Some(vigenere) => vigenere.encrypt(&self.to_encrypt),
-                    None => self.to_encrypt.clone(),
-                };
+                self.encrypt_vigenere();
+            }
+            CipherMessage::VigenereMode(mode) => {
+                self.vigenere_mode = mode;
+                self.encrypt_vigenere();
             }
             CipherMessage::InputChanged(input) => {
                 self.to_encrypt = input;
@@ -90,7 +378,16 @@ impl AppScreen for CipherState {
                 self.vigenere_encrypted = match &self.vigenere_cipher {
                     Some(vigenere) => vigenere.encrypt(&self.to_encrypt),
                     None => self.to_encrypt.clone(),
-                }
+                };
+                self.encrypt_chosen();
+            }
+            CipherMessage::Kind(kind) => {
+                self.kind = kind;
+                self.encrypt_chosen();
+            }
+            CipherMessage::Key(key) => {
+                self.key = key;
+                self.encrypt_chosen();
             }
         }
     }
@@ -1,19 +1,125 @@
-use iced::widget::{button, text, text_input};
+mod catalog;
+mod compare;
+mod generate;
+mod haggle;
+mod ledger;
+mod party;
+mod regions;
+mod roll;
+
+use std::fmt;
+
+use iced::widget::{button, pick_list, row, text, text_input};
 use iced::{
     Element,
-    widget::{TextInput, column, container},
+    widget::{TextInput, column, container, scrollable},
 };
 
-use crate::model::fence::{Percentage, PercentageError, parse_human_percentage};
+use crate::model::campaign::{Calendar, Campaign, CampaignError};
+use crate::model::catalog::{Catalog, Category, LootBag};
+use crate::model::fence::bulk::{VolumeTierError, VolumeTiers};
+use crate::model::fence::generate::{Locks, NameLists, Personality, generate};
+use crate::model::fence::{
+    Fence, FenceError, FenceField, Percentage, PercentageError, ReputationCurve,
+    ReputationCurveError, RoundingMode, Side, Spread,
+};
+use crate::model::heat::{Heat, HeatError, HeatTolerance};
+use crate::model::ledger::Totals;
+use crate::model::money::{Denominations, Money, MoneyError};
+use crate::model::party::Party;
+use crate::model::region::{Location, Regions};
 use crate::ui::components::modal::modal;
-use crate::ui::components::{card::card, fence_card::fence_card, layout::vert_stack};
+use crate::ui::components::{
+    card::card,
+    fence_card::{CardPanels, QuoteInputs, fence_card},
+    layout::vert_stack,
+};
+use crate::ui::screens::fence::catalog::{CatalogMessage, CatalogPanel};
+use crate::ui::screens::fence::compare::{CompareInputs, CompareMessage, ComparePanel};
+use crate::ui::screens::fence::generate::{GenerateMessage, GeneratorPanel};
+use crate::ui::screens::fence::haggle::{HaggleMessage, HagglePanel};
+use crate::ui::screens::fence::ledger::{DealContext, LedgerMessage, LedgerPanel, describe};
+use crate::ui::screens::fence::party::{PartyMessage, PartyPanel};
+use crate::ui::screens::fence::regions::{RegionMessage, RegionPanel};
+use crate::ui::screens::fence::roll::{RollMessage, RollPanel};
 
-use crate::{app::AppScreen, model::fence::Fence};
+use crate::app::AppScreen;
 
 enum EditState {
     Idle,
     Editing { draft: EditDraft, index: usize },
     Creating { draft: EditDraft },
+    Deleting { index: usize },
+}
+
+/// How to take back a change to the fence list.
+enum Undo {
+    Remove { index: usize },
+    Restore { index: usize, fence: Box<Fence> },
+    Swap { a: usize, b: usize },
+}
+
+const UNDO_LIMIT: usize = 50;
+
+/// Which fences the screen shows: all of them, or those working in one region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionFilter(Option<String>);
+
+impl fmt::Display for RegionFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(region) => write!(f, "{region}"),
+            None => write!(f, "All regions"),
+        }
+    }
+}
+
+/// Where a fence works, as picked in the editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocationChoice(Option<Location>);
+
+impl fmt::Display for LocationChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(location) => write!(f, "{location}"),
+            None => write!(f, "Anywhere"),
+        }
+    }
+}
+
+/// A fence's personality, as picked in the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PersonalityChoice(Option<Personality>);
+
+impl fmt::Display for PersonalityChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(personality) => write!(f, "{personality}"),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+/// How a fence treats one category of goods, as picked in the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stance {
+    Deals,
+    Specialty,
+    Refuses,
+}
+
+impl Stance {
+    const ALL: [Stance; 3] = [Stance::Deals, Stance::Specialty, Stance::Refuses];
+}
+
+impl fmt::Display for Stance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stance::Deals => write!(f, "Deals in"),
+            Stance::Specialty => write!(f, "Specialises in"),
+            Stance::Refuses => write!(f, "Refuses"),
+        }
+    }
 }
 
 #[derive(Default)]
@@ -23,14 +129,38 @@ struct EditDraft {
     lowest_markup: String,
     avg_markup: String,
     highest_markup: String,
+    buy_lowest: String,
+    buy_avg: String,
+    buy_highest: String,
+    curve: String,
+    heat_comfort: String,
+    heat_limit: String,
+    heat_risk: String,
+    volume_tiers: String,
+    weekly_cash: String,
+    location: Option<Location>,
+    personality: Option<Personality>,
+    specialties: Vec<Category>,
+    refused: Vec<Category>,
+    rounding: RoundingMode,
     errors: EditErrors,
 }
 
 #[derive(Default)]
 struct EditErrors {
+    fence: Vec<FenceError>,
     lowest: Option<PercentageError>,
     average: Option<PercentageError>,
     highest: Option<PercentageError>,
+    buy_lowest: Option<PercentageError>,
+    buy_avg: Option<PercentageError>,
+    buy_highest: Option<PercentageError>,
+    curve: Option<ReputationCurveError>,
+    heat_comfort: Option<HeatError>,
+    heat_limit: Option<HeatError>,
+    heat_risk: Option<ReputationCurveError>,
+    volume_tiers: Option<VolumeTierError>,
+    weekly_cash: Option<MoneyError>,
 }
 
 #[derive(Debug, Clone)]
@@ -43,19 +173,84 @@ pub enum FenceMessage {
     Lowest(String),
     Avg(String),
     Highest(String),
+    BuyLowest(String),
+    BuyAvg(String),
+    BuyHighest(String),
+    Curve(String),
+    HeatComfort(String),
+    HeatLimit(String),
+    HeatRisk(String),
+    VolumeTiers(String),
+    WeeklyCash(String),
+    Location(LocationChoice),
+    Personality(PersonalityChoice),
+    Stance(Category, Stance),
+    Rounding(RoundingMode),
     Edit(usize),
     Submit,
+    OpenHaggle(usize),
+    CloseHaggle,
+    Haggle(HaggleMessage),
+    Catalog(CatalogMessage),
+    Denominations(String),
+    GlobalRounding(RoundingMode),
+    OpenLedger(usize),
+    CloseLedger,
+    Ledger(LedgerMessage),
+    NextDay,
+    NextSession,
+    ExportLedger,
+    Duplicate(usize),
+    Delete(usize),
+    ConfirmDelete,
+    MoveUp(usize),
+    MoveDown(usize),
+    Undo,
+    OpenRoll(usize),
+    CloseRoll,
+    Roll(RollMessage),
+    Compare(CompareMessage),
+    Regions(RegionMessage),
+    RegionFilter(RegionFilter),
+    Party(PartyMessage),
+    SplitQuote(usize),
+    GenerateFence,
+    Generate(GenerateMessage),
 }
 
 pub struct FenceState {
     fences: Vec<Fence>,
+    catalog: Catalog,
+    loot: LootBag,
+    denominations: Denominations,
+    denominations_input: String,
+    denominations_error: Option<String>,
+    rounding: RoundingMode,
+    calendar: Calendar,
+    regions: Regions,
+    region_filter: RegionFilter,
+    party: Party,
+    names: NameLists,
 
     base_price_input: String,
-    parsed_base_price: Option<u64>,
+    parsed_base_price: Option<Money>,
 
     error: Option<String>,
+    storage_error: Option<String>,
+    /// The campaign file on disk couldn't be loaded, so it's backed up before the first save.
+    unreadable_file: bool,
+    export_status: Option<String>,
 
     create_fence_state: EditState,
+    haggle: Option<HagglePanel>,
+    ledger: Option<LedgerPanel>,
+    roll: Option<RollPanel>,
+    generator: Option<GeneratorPanel>,
+    undo: Vec<Undo>,
+    catalog_panel: CatalogPanel,
+    compare_panel: ComparePanel,
+    region_panel: RegionPanel,
+    party_panel: PartyPanel,
 }
 
 impl Default for FenceState {
@@ -72,21 +267,196 @@ impl Default for FenceState {
                 ),
             ]
             .to_vec(),
+            catalog: Catalog::default(),
+            loot: LootBag::default(),
+            denominations: Denominations::default(),
+            denominations_input: Denominations::default().to_string(),
+            denominations_error: None,
+            rounding: RoundingMode::default(),
+            calendar: Calendar::default(),
+            regions: Regions::default(),
+            region_filter: RegionFilter(None),
+            party: Party::default(),
+            names: NameLists::default(),
             base_price_input: "".into(),
-            parsed_base_price: Some(0),
+            parsed_base_price: Some(Money(0)),
             error: None,
+            storage_error: None,
+            unreadable_file: false,
+            export_status: None,
             create_fence_state: EditState::Idle,
+            haggle: None,
+            ledger: None,
+            roll: None,
+            generator: None,
+            undo: Vec::new(),
+            catalog_panel: CatalogPanel::default(),
+            compare_panel: ComparePanel::default(),
+            region_panel: RegionPanel::default(),
+            party_panel: PartyPanel::default(),
         }
     }
 }
 
 impl FenceState {
+    pub fn from_campaign(campaign: Result<Campaign, CampaignError>) -> Self {
+        match campaign {
+            Ok(campaign) => FenceState {
+                fences: campaign.fences,
+                catalog: campaign.catalog,
+                loot: campaign.loot,
+                denominations_input: campaign.denominations.to_string(),
+                party_panel: PartyPanel::new(&campaign.party, &campaign.denominations),
+                denominations: campaign.denominations,
+                rounding: campaign.rounding,
+                calendar: campaign.calendar,
+                regions: campaign.regions,
+                party: campaign.party,
+                names: campaign.names,
+                ..FenceState::default()
+            },
+            Err(err @ (CampaignError::NotFound(_) | CampaignError::NoDataDir)) => FenceState {
+                storage_error: Some(err.to_string()),
+                ..FenceState::default()
+            },
+            Err(err) => FenceState {
+                storage_error: Some(format!(
+                    "{err}. It will be kept as a .bak file before anything is saved"
+                )),
+                unreadable_file: true,
+                ..FenceState::default()
+            },
+        }
+    }
+
+    fn campaign(&self) -> Campaign {
+        Campaign {
+            fences: self.fences.clone(),
+            catalog: self.catalog.clone(),
+            loot: self.loot.clone(),
+            denominations: self.denominations.clone(),
+            rounding: self.rounding,
+            calendar: self.calendar,
+            regions: self.regions.clone(),
+            party: self.party.clone(),
+            names: self.names.clone(),
+        }
+    }
+
+    fn save(&mut self) {
+        let backup = match self.unreadable_file {
+            true => match Campaign::back_up() {
+                Ok(path) => Some(path),
+                Err(err) => {
+                    self.storage_error = Some(format!(
+                        "Not saving over the unreadable campaign file: {err}"
+                    ));
+                    return;
+                }
+            },
+            false => None,
+        };
+        self.unreadable_file = false;
+        self.storage_error = match (self.campaign().save(), backup) {
+            (Err(err), _) => Some(err.to_string()),
+            (Ok(()), Some(path)) => Some(format!(
+                "The unreadable campaign file was kept as {}",
+                path.display()
+            )),
+            (Ok(()), None) => None,
+        };
+    }
+
+    fn deal_context(&self) -> DealContext<'_> {
+        DealContext {
+            loot: &self.loot,
+            catalog: &self.catalog,
+            base_price: self.parsed_base_price,
+            calendar: self.calendar,
+            coins: &self.denominations,
+            regions: &self.regions,
+        }
+    }
+
+    fn parse_base_price(&mut self) {
+        match self.denominations.parse(&self.base_price_input) {
+            Ok(value) => {
+                self.parsed_base_price = Some(value);
+                self.error = None;
+            }
+            Err(err) => {
+                self.parsed_base_price = None;
+                self.error = Some(err.to_string());
+            }
+        }
+    }
+
     fn current_draft_mut(&mut self) -> Option<&mut EditDraft> {
         match &mut self.create_fence_state {
             EditState::Creating { draft } => Some(draft),
             EditState::Editing { draft, .. } => Some(draft),
-            EditState::Idle => None,
+            EditState::Idle | EditState::Deleting { .. } => None,
+        }
+    }
+
+    /// Validates the open editor against the other fences, returning the fence it
+    /// describes once everything checks out.
+    fn validate_draft(&mut self, submitting: bool) -> Option<Fence> {
+        let editing = match self.create_fence_state {
+            EditState::Editing { index, .. } => Some(index),
+            _ => None,
+        };
+        let names: Vec<String> = self
+            .fences
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != editing)
+            .map(|(_, fence)| fence.name.clone())
+            .collect();
+        let taken: Vec<&str> = names.iter().map(String::as_str).collect();
+        let coins = self.denominations.clone();
+        self.current_draft_mut()?
+            .validate(&taken, &coins, submitting)
+    }
+
+    /// Records how to take back a change to the fence list. Panels point at fences by
+    /// index, so they close whenever fences come, go or move.
+    fn remember(&mut self, undo: Undo) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
         }
+        self.undo.push(undo);
+        self.close_panels();
+    }
+
+    fn close_panels(&mut self) {
+        self.haggle = None;
+        self.ledger = None;
+        self.roll = None;
+    }
+
+    fn is_shown(&self, fence: &Fence) -> bool {
+        self.region_filter.0.as_ref().is_none_or(|region| {
+            fence
+                .location
+                .as_ref()
+                .is_some_and(|location| &location.region == region)
+        })
+    }
+
+    /// The fence shown next to the one at `index`, skipping any the region filter hides.
+    fn shown_neighbour(&self, index: usize, up: bool) -> Option<usize> {
+        let shown = |i: &usize| self.is_shown(&self.fences[*i]);
+        match up {
+            true => (0..index.min(self.fences.len())).rev().find(shown),
+            false => (index + 1..self.fences.len()).find(shown),
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.fences.swap(a, b);
+        self.remember(Undo::Swap { a, b });
+        self.save();
     }
 }
 
@@ -94,51 +464,224 @@ impl AppScreen for FenceState {
     type Msg = FenceMessage;
 
     fn view(&self) -> Element<'_, FenceMessage> {
-        let col = vert_stack().push(column(self.fences.iter().enumerate().map(|(i, fence)| {
+        let inputs = QuoteInputs {
+            base_price: self.parsed_base_price,
+            error: &self.error,
+            loot: &self.loot,
+            coins: &self.denominations,
+            day: self.calendar.day,
+            regions: &self.regions,
+        };
+        let shown = self
+            .fences
+            .iter()
+            .enumerate()
+            .filter(|(_, fence)| self.is_shown(fence));
+        let col = vert_stack().push(column(shown.map(|(i, fence)| {
+            let haggle = self
+                .haggle
+                .as_ref()
+                .filter(|panel| panel.index == i)
+                .map(|panel| panel.view(&self.denominations).map(FenceMessage::Haggle));
+            let ledger = self
+                .ledger
+                .as_ref()
+                .filter(|panel| panel.index == i)
+                .map(|panel| {
+                    panel
+                        .view(fence, &self.denominations)
+                        .map(FenceMessage::Ledger)
+                });
+            let roll = self
+                .roll
+                .as_ref()
+                .filter(|panel| panel.index == i)
+                .map(|panel| panel.view(&self.denominations).map(FenceMessage::Roll));
             fence_card(
+                i,
                 fence,
-                self.parsed_base_price,
-                &self.error,
-                FenceMessage::Edit(i),
+                &inputs,
+                CardPanels {
+                    haggle,
+                    ledger,
+                    roll,
+                },
             )
         })));
-        let base = card(
+        let transactions = self
+            .fences
+            .iter()
+            .flat_map(|fence| &fence.ledger.transactions);
+        let totals =
+            Totals::by(transactions.clone(), |t| t.character.clone())
+                .into_iter()
+                .map(|(character, totals)| {
+                    format!("{character}: {}", describe(&totals, &self.denominations))
+                })
+                .chain(Totals::by(transactions, |t| t.session).into_iter().map(
+                    |(session, totals)| {
+                        format!(
+                            "Session {session}: {}",
+                            describe(&totals, &self.denominations)
+                        )
+                    },
+                ))
+                .map(|line| text(line).into());
+        let calendar = column![
+            row![
+                text(format!(
+                    "Day {}, session {}",
+                    self.calendar.day, self.calendar.session
+                )),
+                button("Next day").on_press(FenceMessage::NextDay),
+                button("Next session").on_press(FenceMessage::NextSession),
+                button("Export ledger as CSV").on_press(FenceMessage::ExportLedger),
+            ]
+            .spacing(10),
+            column(totals).spacing(2),
+        ]
+        .push(self.export_status.as_ref().map(text))
+        .spacing(5);
+        let filters: Vec<RegionFilter> = std::iter::once(RegionFilter(None))
+            .chain(
+                self.regions
+                    .0
+                    .iter()
+                    .map(|region| RegionFilter(Some(region.name.clone()))),
+            )
+            .collect();
+        let base = card(scrollable(
             vert_stack()
-                .push(button("Add").on_press(FenceMessage::ShowModal))
+                .push(self.storage_error.as_ref().map(text))
+                .push(calendar)
+                .push(
+                    row![
+                        button("Add").on_press(FenceMessage::ShowModal),
+                        button("Generate fence").on_press(FenceMessage::GenerateFence),
+                        button("Undo")
+                            .on_press_maybe((!self.undo.is_empty()).then_some(FenceMessage::Undo)),
+                        text("Rounding for all fences"),
+                        pick_list(
+                            &RoundingMode::ALL[..],
+                            Some(self.rounding),
+                            FenceMessage::GlobalRounding
+                        ),
+                    ]
+                    .spacing(10),
+                )
+                .push(
+                    column![
+                        text("Denominations").size(12),
+                        text_input("pp=1000, gp=100, sp=10, cp=1", &self.denominations_input)
+                            .on_input(FenceMessage::Denominations),
+                    ]
+                    .push(self.denominations_error.as_ref().map(text)),
+                )
                 .push(
-                    TextInput::new("Enter base price", &self.base_price_input)
+                    TextInput::new("Enter base price, i.e. 12gp 5sp", &self.base_price_input)
                         .padding(10)
                         .on_input(FenceMessage::BaseInputChanged),
                 )
-                .height(1200)
+                .push(
+                    self.catalog_panel
+                        .view(
+                            &self.catalog,
+                            &self.loot,
+                            &self.denominations,
+                            self.calendar.day,
+                        )
+                        .map(FenceMessage::Catalog),
+                )
+                .push(
+                    self.compare_panel
+                        .view(&CompareInputs {
+                            fences: &self.fences,
+                            regions: &self.regions,
+                            catalog: &self.catalog,
+                            loot: &self.loot,
+                            base_price: self.parsed_base_price,
+                            day: self.calendar.day,
+                            coins: &self.denominations,
+                        })
+                        .map(FenceMessage::Compare),
+                )
+                .push(
+                    self.region_panel
+                        .view(&self.regions)
+                        .map(FenceMessage::Regions),
+                )
+                .push(
+                    self.party_panel
+                        .view(&self.party, &self.denominations)
+                        .map(FenceMessage::Party),
+                )
+                .push(
+                    row![
+                        text("Show fences in"),
+                        pick_list(
+                            filters,
+                            Some(self.region_filter.clone()),
+                            FenceMessage::RegionFilter
+                        ),
+                    ]
+                    .spacing(10),
+                )
                 .push(col),
-        );
+        ));
 
         match &self.create_fence_state {
             EditState::Idle => base,
-            EditState::Creating { draft } => {
-                modal(base, fence_modal(draft, false), FenceMessage::HideModal)
-            }
-            EditState::Editing { draft, .. } => {
-                modal(base, fence_modal(draft, true), FenceMessage::HideModal)
-            }
+            EditState::Creating { draft } => modal(
+                base,
+                fence_modal(draft, &self.regions, self.generator.as_ref(), false),
+                FenceMessage::HideModal,
+            ),
+            EditState::Editing { draft, .. } => modal(
+                base,
+                fence_modal(draft, &self.regions, None, true),
+                FenceMessage::HideModal,
+            ),
+            EditState::Deleting { index } => match self.fences.get(*index) {
+                Some(fence) => modal(base, delete_modal(fence), FenceMessage::HideModal),
+                None => base,
+            },
         }
     }
 
     fn update(&mut self, message: FenceMessage) {
+        let edits_draft = matches!(
+            message,
+            FenceMessage::Name(_)
+                | FenceMessage::Reputation(_)
+                | FenceMessage::Lowest(_)
+                | FenceMessage::Avg(_)
+                | FenceMessage::Highest(_)
+                | FenceMessage::BuyLowest(_)
+                | FenceMessage::BuyAvg(_)
+                | FenceMessage::BuyHighest(_)
+                | FenceMessage::Curve(_)
+                | FenceMessage::HeatComfort(_)
+                | FenceMessage::HeatLimit(_)
+                | FenceMessage::HeatRisk(_)
+                | FenceMessage::VolumeTiers(_)
+                | FenceMessage::WeeklyCash(_)
+        );
         match message {
             FenceMessage::BaseInputChanged(input) => {
-                self.base_price_input = input.clone();
-                match input.parse::<u64>() {
-                    Ok(value) => {
-                        self.parsed_base_price = Some(value);
-                        self.error = None;
-                    }
-                    Err(_) => {
-                        self.parsed_base_price = None;
-                        self.error = Some("Invalid number".into());
+                self.base_price_input = input;
+                self.parse_base_price();
+            }
+            FenceMessage::Denominations(input) => {
+                match input.parse::<Denominations>() {
+                    Ok(denominations) => {
+                        self.denominations = denominations;
+                        self.denominations_error = None;
+                        self.parse_base_price();
+                        self.save();
                     }
+                    Err(err) => self.denominations_error = Some(err.to_string()),
                 }
+                self.denominations_input = input;
             }
             FenceMessage::Name(name) => {
                 if let Some(draft) = self.current_draft_mut() {
@@ -165,112 +708,635 @@ impl AppScreen for FenceState {
                     draft.highest_markup = high;
                 }
             }
+            FenceMessage::BuyLowest(low) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.buy_lowest = low;
+                }
+            }
+            FenceMessage::BuyAvg(avg) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.buy_avg = avg;
+                }
+            }
+            FenceMessage::BuyHighest(high) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.buy_highest = high;
+                }
+            }
+            FenceMessage::Curve(curve) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.curve = curve;
+                }
+            }
+            FenceMessage::HeatComfort(comfort) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.heat_comfort = comfort;
+                }
+            }
+            FenceMessage::HeatLimit(limit) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.heat_limit = limit;
+                }
+            }
+            FenceMessage::HeatRisk(risk) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.heat_risk = risk;
+                }
+            }
+            FenceMessage::VolumeTiers(tiers) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.volume_tiers = tiers;
+                }
+            }
+            FenceMessage::WeeklyCash(cash) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.weekly_cash = cash;
+                }
+            }
+            FenceMessage::Location(choice) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.location = choice.0;
+                }
+            }
+            FenceMessage::Personality(choice) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.personality = choice.0;
+                }
+            }
+            FenceMessage::Stance(category, stance) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.set_stance(category, stance);
+                }
+            }
+            FenceMessage::Rounding(rounding) => {
+                if let Some(draft) = self.current_draft_mut() {
+                    draft.rounding = rounding;
+                }
+            }
+            FenceMessage::GlobalRounding(rounding) => {
+                self.rounding = rounding;
+                for fence in &mut self.fences {
+                    fence.rounding = rounding;
+                }
+                self.save();
+            }
             FenceMessage::ShowModal => {
+                let Fence {
+                    buy,
+                    heat_tolerance,
+                    ..
+                } = Fence::default();
                 self.create_fence_state = EditState::Creating {
-                    draft: EditDraft::default(),
+                    draft: EditDraft {
+                        heat_comfort: heat_tolerance.comfort.to_string(),
+                        heat_limit: heat_tolerance.limit.to_string(),
+                        heat_risk: heat_tolerance.risk.to_string(),
+                        buy_lowest: buy.lowest.to_string(),
+                        buy_avg: buy.avg.to_string(),
+                        buy_highest: buy.highest.to_string(),
+                        curve: ReputationCurve::default().to_string(),
+                        rounding: self.rounding,
+                        ..EditDraft::default()
+                    },
                 };
             }
             FenceMessage::Edit(index) => {
                 if let Some(fence) = self.fences.get(index) {
                     self.create_fence_state = EditState::Editing {
                         index,
+                        draft: EditDraft::from_fence(fence, &self.denominations),
+                    }
+                }
+            }
+            FenceMessage::Duplicate(index) => {
+                if let Some(fence) = self.fences.get(index) {
+                    self.create_fence_state = EditState::Creating {
                         draft: EditDraft {
-                            name: fence.name.clone(),
-                            rep: fence.reputation.to_string(),
-                            lowest_markup: fence.lowest_markup.to_string(),
-                            avg_markup: fence.avg_markup.to_string(),
-                            highest_markup: fence.highest_markup.to_string(),
-                            errors: EditErrors::default(),
+                            name: format!("{} (copy)", fence.name),
+                            ..EditDraft::from_fence(fence, &self.denominations)
                         },
                     }
                 }
             }
-            FenceMessage::HideModal => self.create_fence_state = EditState::Idle,
-            FenceMessage::Submit => {
-                if let Some(draft) = self.current_draft_mut() {
-                    let lowest = parse_human_percentage(&draft.lowest_markup);
-                    let average = parse_human_percentage(&draft.avg_markup);
-                    let highest = parse_human_percentage(&draft.highest_markup);
-
-                    draft.errors = EditErrors {
-                        lowest: lowest.as_ref().err().cloned(),
-                        average: average.as_ref().err().cloned(),
-                        highest: highest.as_ref().err().cloned(),
-                    };
-
-                    if let (Ok(rep), Ok(lowest), Ok(average), Ok(highest)) =
-                        (draft.rep.parse::<u8>(), lowest, average, highest)
+            FenceMessage::Delete(index) => {
+                if index < self.fences.len() {
+                    self.create_fence_state = EditState::Deleting { index };
+                }
+            }
+            FenceMessage::ConfirmDelete => {
+                if let EditState::Deleting { index } = self.create_fence_state
+                    && index < self.fences.len()
+                {
+                    let fence = self.fences.remove(index);
+                    self.remember(Undo::Restore {
+                        index,
+                        fence: Box::new(fence),
+                    });
+                    self.save();
+                }
+                self.create_fence_state = EditState::Idle;
+            }
+            FenceMessage::MoveUp(index) => {
+                if index < self.fences.len()
+                    && let Some(above) = self.shown_neighbour(index, true)
+                {
+                    self.swap(above, index);
+                }
+            }
+            FenceMessage::MoveDown(index) => {
+                if let Some(below) = self.shown_neighbour(index, false) {
+                    self.swap(index, below);
+                }
+            }
+            FenceMessage::Undo => {
+                if let Some(undo) = self.undo.pop() {
+                    match undo {
+                        Undo::Remove { index } if index < self.fences.len() => {
+                            self.fences.remove(index);
+                        }
+                        Undo::Restore { index, fence } if index <= self.fences.len() => {
+                            self.fences.insert(index, *fence);
+                        }
+                        Undo::Swap { a, b } if a.max(b) < self.fences.len() => {
+                            self.fences.swap(a, b);
+                        }
+                        _ => {}
+                    }
+                    self.close_panels();
+                    self.save();
+                }
+            }
+            FenceMessage::HideModal => {
+                self.create_fence_state = EditState::Idle;
+                self.generator = None;
+            }
+            FenceMessage::GenerateFence => {
+                let seed = rand::random();
+                let names: Vec<&str> = self.fences.iter().map(|f| f.name.as_str()).collect();
+                let fence = generate(&self.names, seed, &names);
+                self.create_fence_state = EditState::Creating {
+                    draft: EditDraft::from_fence(&fence, &self.denominations),
+                };
+                self.generator = Some(GeneratorPanel::new(seed, &self.names));
+            }
+            FenceMessage::Generate(msg) => {
+                let edits_names = msg.edits_names();
+                let names: Vec<&str> = self.fences.iter().map(|f| f.name.as_str()).collect();
+                if let Some(panel) = &mut self.generator
+                    && let EditState::Creating { draft } = &mut self.create_fence_state
+                    && let Some(fence) =
+                        panel.update(msg, &mut self.names, draft.personality, &names)
+                {
+                    draft.reroll(&fence, panel.locks, &self.denominations);
+                }
+                if edits_names {
+                    self.save();
+                }
+            }
+            FenceMessage::Catalog(msg) => {
+                if self.catalog_panel.update(
+                    msg,
+                    &mut self.catalog,
+                    &mut self.loot,
+                    &self.denominations,
+                    self.calendar.day,
+                ) {
+                    self.save();
+                }
+            }
+            FenceMessage::OpenHaggle(index) => self.haggle = Some(HagglePanel::new(index)),
+            FenceMessage::CloseHaggle => self.haggle = None,
+            FenceMessage::Haggle(HaggleMessage::Record) => {
+                if let Some(panel) = &self.haggle
+                    && let Some(price) = panel.deal()
+                    && let Some(fence) = self.fences.get(panel.index)
+                {
+                    let market = self.regions.market(fence.location.as_ref());
+                    let quoted = self
+                        .parsed_base_price
+                        .and_then(|base| fence.price_band(Side::Sell, base.0, &market).ok())
+                        .map_or(price, |band| Money(band.high));
+                    self.ledger = Some(LedgerPanel::for_haggle(
+                        panel.index,
+                        quoted,
+                        price,
+                        &self.denominations,
+                    ));
+                }
+            }
+            FenceMessage::Haggle(msg) => {
+                if let Some(panel) = &mut self.haggle
+                    && let Some(fence) = self.fences.get(panel.index)
+                {
+                    let market = self.regions.market(fence.location.as_ref());
+                    panel.update(
+                        msg,
+                        fence,
+                        &market,
+                        self.parsed_base_price,
+                        &self.denominations,
+                    );
+                }
+            }
+            FenceMessage::OpenLedger(index) => {
+                if let Some(fence) = self.fences.get(index) {
+                    self.ledger = Some(LedgerPanel::new(index, fence, &self.deal_context()));
+                }
+            }
+            FenceMessage::CloseLedger => self.ledger = None,
+            FenceMessage::OpenRoll(index) => {
+                self.roll = Some(RollPanel::new(index, &self.deal_context()));
+            }
+            FenceMessage::CloseRoll => self.roll = None,
+            FenceMessage::Compare(msg) => self.compare_panel.update(msg),
+            FenceMessage::Regions(msg) => {
+                if self
+                    .region_panel
+                    .update(msg, &mut self.regions, &self.fences)
+                {
+                    if let RegionFilter(Some(region)) = &self.region_filter
+                        && self.regions.region(region).is_none()
                     {
-                        let new_fence = Fence {
-                            name: draft.name.clone(),
-                            reputation: rep,
-                            lowest_markup: lowest,
-                            avg_markup: average,
-                            highest_markup: highest,
-                        };
-
-                        match &self.create_fence_state {
-                            EditState::Creating { .. } => self.fences.push(new_fence),
-                            EditState::Editing { index, .. } => {
-                                if let Some(slot) = self.fences.get_mut(*index) {
-                                    *slot = new_fence;
-                                }
+                        self.region_filter = RegionFilter(None);
+                    }
+                    self.save();
+                }
+            }
+            FenceMessage::RegionFilter(filter) => self.region_filter = filter,
+            FenceMessage::Party(msg) => {
+                if self
+                    .party_panel
+                    .update(msg, &mut self.party, self.calendar, &self.denominations)
+                {
+                    self.save();
+                }
+            }
+            FenceMessage::SplitQuote(index) => {
+                if let Some(fence) = self.fences.get(index)
+                    && let Some(band) = self.deal_context().band(fence, Side::Buy)
+                {
+                    self.party_panel.update(
+                        PartyMessage::Propose {
+                            source: format!("Quote from {}", fence.name),
+                            amount: Money(band.avg),
+                        },
+                        &mut self.party,
+                        self.calendar,
+                        &self.denominations,
+                    );
+                }
+            }
+            FenceMessage::Roll(msg) => {
+                let deal = DealContext {
+                    loot: &self.loot,
+                    catalog: &self.catalog,
+                    base_price: self.parsed_base_price,
+                    calendar: self.calendar,
+                    coins: &self.denominations,
+                    regions: &self.regions,
+                };
+                if let Some(panel) = &mut self.roll
+                    && let Some(fence) = self.fences.get(panel.index)
+                {
+                    panel.update(msg, fence, &deal);
+                }
+            }
+            FenceMessage::Ledger(LedgerMessage::Split(entry)) => {
+                if let Some(panel) = &self.ledger
+                    && let Some(fence) = self.fences.get(panel.index)
+                    && let Some(transaction) = fence.ledger.transactions.get(entry)
+                {
+                    self.party_panel.update(
+                        PartyMessage::Propose {
+                            source: format!(
+                                "{}'s sale to {} on day {}",
+                                transaction.character, fence.name, transaction.day
+                            ),
+                            amount: transaction.price,
+                        },
+                        &mut self.party,
+                        self.calendar,
+                        &self.denominations,
+                    );
+                }
+            }
+            FenceMessage::Ledger(msg) => {
+                let deal = DealContext {
+                    loot: &self.loot,
+                    catalog: &self.catalog,
+                    base_price: self.parsed_base_price,
+                    calendar: self.calendar,
+                    coins: &self.denominations,
+                    regions: &self.regions,
+                };
+                if let Some(panel) = &mut self.ledger
+                    && let Some(fence) = self.fences.get_mut(panel.index)
+                    && panel.update(msg, fence, &deal)
+                {
+                    self.save();
+                }
+            }
+            FenceMessage::NextDay => {
+                self.calendar.day += 1;
+                self.save();
+            }
+            FenceMessage::NextSession => {
+                self.calendar.session += 1;
+                self.save();
+            }
+            FenceMessage::ExportLedger => {
+                self.export_status = Some(match self.campaign().export_ledger() {
+                    Ok(path) => format!("Ledger exported to {}", path.display()),
+                    Err(err) => err.to_string(),
+                });
+            }
+            FenceMessage::Submit => {
+                if let Some(new_fence) = self.validate_draft(true) {
+                    match &self.create_fence_state {
+                        EditState::Creating { .. } => {
+                            self.fences.push(new_fence);
+                            self.remember(Undo::Remove {
+                                index: self.fences.len() - 1,
+                            });
+                        }
+                        EditState::Editing { index, .. } => {
+                            if let Some(slot) = self.fences.get_mut(*index) {
+                                *slot = Fence {
+                                    ledger: std::mem::take(&mut slot.ledger),
+                                    ..new_fence
+                                };
                             }
-                            EditState::Idle => {}
                         }
-
-                        self.create_fence_state = EditState::Idle;
+                        EditState::Idle | EditState::Deleting { .. } => {}
                     }
+
+                    self.create_fence_state = EditState::Idle;
+                    self.generator = None;
+                    self.save();
                 }
             }
         }
+
+        if edits_draft {
+            self.validate_draft(false);
+        }
     }
 }
 
-fn fence_modal<'a>(draft: &EditDraft, is_editing: bool) -> Element<'a, FenceMessage> {
-    let label = if is_editing { "Save" } else { "Create" };
+impl EditErrors {
+    /// Everything wrong with one of the fence's own fields, parse errors first.
+    fn for_field(&self, field: FenceField) -> String {
+        let parse = match field {
+            FenceField::Lowest(Side::Sell) => self.lowest.as_ref(),
+            FenceField::Average(Side::Sell) => self.average.as_ref(),
+            FenceField::Highest(Side::Sell) => self.highest.as_ref(),
+            FenceField::Lowest(Side::Buy) => self.buy_lowest.as_ref(),
+            FenceField::Average(Side::Buy) => self.buy_avg.as_ref(),
+            FenceField::Highest(Side::Buy) => self.buy_highest.as_ref(),
+            FenceField::Name | FenceField::Reputation => None,
+        };
+        parse
+            .map(ToString::to_string)
+            .into_iter()
+            .chain(
+                self.fence
+                    .iter()
+                    .filter(|err| err.field() == field)
+                    .map(ToString::to_string),
+            )
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl EditDraft {
+    /// Checks every field and keeps the errors for the modal. Blank fields are only
+    /// flagged on submit, so a fresh form doesn't start out covered in errors.
+    fn validate(
+        &mut self,
+        taken_names: &[&str],
+        coins: &Denominations,
+        submitting: bool,
+    ) -> Option<Fence> {
+        let shown = |input: &str| submitting || !input.trim().is_empty();
+        let lowest = self.lowest_markup.parse::<Percentage>();
+        let average = self.avg_markup.parse::<Percentage>();
+        let highest = self.highest_markup.parse::<Percentage>();
+        let buy_lowest = self.buy_lowest.parse::<Percentage>();
+        let buy_avg = self.buy_avg.parse::<Percentage>();
+        let buy_highest = self.buy_highest.parse::<Percentage>();
+        let curve = self.curve.parse::<ReputationCurve>();
+        let heat_comfort = self.heat_comfort.parse::<Heat>();
+        let heat_limit = self
+            .heat_limit
+            .parse::<Heat>()
+            .and_then(|limit| match heat_comfort {
+                Ok(comfort) => HeatTolerance::check_limit(comfort, limit),
+                Err(_) => Ok(limit),
+            });
+        let heat_risk = self.heat_risk.parse::<ReputationCurve>();
+        let volume_tiers = VolumeTiers::parse(&self.volume_tiers, coins);
+        let weekly_cash = match self.weekly_cash.trim() {
+            "" => Ok(None),
+            cash => coins.parse(cash).map(Some),
+        };
+        let rep = self
+            .rep
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| FenceError::ReputationOutOfRange {
+                max: Fence::MAX_REPUTATION,
+            })
+            .and_then(|rep| Fence::check_reputation(rep).map(|()| rep));
+        let spread = |lowest: &Result<Percentage, PercentageError>,
+                      avg: &Result<Percentage, PercentageError>,
+                      highest: &Result<Percentage, PercentageError>| {
+            Some(Spread {
+                lowest: lowest.clone().ok()?,
+                avg: avg.clone().ok()?,
+                highest: highest.clone().ok()?,
+            })
+        };
+        let sell = spread(&lowest, &average, &highest);
+        let buy = spread(&buy_lowest, &buy_avg, &buy_highest);
+
+        let mut fence = Vec::new();
+        if shown(&self.name) {
+            fence.extend(Fence::check_name(&self.name, taken_names).err());
+        }
+        if shown(&self.rep) {
+            fence.extend(rep.as_ref().err().cloned());
+        }
+        fence.extend(sell.iter().flat_map(|sell| sell.check(Side::Sell)));
+        fence.extend(buy.iter().flat_map(|buy| buy.check(Side::Buy)));
+
+        self.errors = EditErrors {
+            fence,
+            lowest: lowest
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.lowest_markup))
+                .cloned(),
+            average: average
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.avg_markup))
+                .cloned(),
+            highest: highest
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.highest_markup))
+                .cloned(),
+            buy_lowest: buy_lowest
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.buy_lowest))
+                .cloned(),
+            buy_avg: buy_avg
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.buy_avg))
+                .cloned(),
+            buy_highest: buy_highest
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.buy_highest))
+                .cloned(),
+            curve: curve.as_ref().err().filter(|_| shown(&self.curve)).cloned(),
+            heat_comfort: heat_comfort
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.heat_comfort))
+                .cloned(),
+            heat_limit: heat_limit
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.heat_limit))
+                .cloned(),
+            heat_risk: heat_risk
+                .as_ref()
+                .err()
+                .filter(|_| shown(&self.heat_risk))
+                .cloned(),
+            volume_tiers: volume_tiers.as_ref().err().cloned(),
+            weekly_cash: weekly_cash.as_ref().err().cloned(),
+        };
+
+        let (Ok(rep), Some(sell), Some(buy), Ok(curve), Ok(comfort), Ok(limit), Ok(risk)) =
+            (rep, sell, buy, curve, heat_comfort, heat_limit, heat_risk)
+        else {
+            return None;
+        };
+        let (Ok(volume_tiers), Ok(weekly_cash)) = (volume_tiers, weekly_cash) else {
+            return None;
+        };
+        let fence = Fence::try_new(&self.name, rep, sell, buy, taken_names).ok()?;
+        Some(Fence {
+            reputation_curve: curve,
+            rounding: self.rounding,
+            heat_tolerance: HeatTolerance {
+                comfort,
+                limit,
+                risk,
+            },
+            volume_tiers,
+            weekly_cash,
+            location: self.location.clone(),
+            personality: self.personality,
+            specialties: self.specialties.clone(),
+            refused: self.refused.clone(),
+            ..fence
+        })
+    }
+
+    fn from_fence(fence: &Fence, coins: &Denominations) -> Self {
+        EditDraft {
+            name: fence.name.clone(),
+            rep: fence.reputation.to_string(),
+            lowest_markup: fence.lowest_markup.to_string(),
+            avg_markup: fence.avg_markup.to_string(),
+            highest_markup: fence.highest_markup.to_string(),
+            buy_lowest: fence.buy.lowest.to_string(),
+            buy_avg: fence.buy.avg.to_string(),
+            buy_highest: fence.buy.highest.to_string(),
+            curve: fence.reputation_curve.to_string(),
+            heat_comfort: fence.heat_tolerance.comfort.to_string(),
+            heat_limit: fence.heat_tolerance.limit.to_string(),
+            heat_risk: fence.heat_tolerance.risk.to_string(),
+            volume_tiers: fence.volume_tiers.format(coins),
+            weekly_cash: fence
+                .weekly_cash
+                .map(|cash| coins.format(cash))
+                .unwrap_or_default(),
+            location: fence.location.clone(),
+            personality: fence.personality,
+            specialties: fence.specialties.clone(),
+            refused: fence.refused.clone(),
+            rounding: fence.rounding,
+            errors: EditErrors::default(),
+        }
+    }
+
+    /// Takes the fields of a freshly generated fence, leaving locked ones as they are.
+    fn reroll(&mut self, fence: &Fence, locks: Locks, coins: &Denominations) {
+        let rolled = EditDraft::from_fence(fence, coins);
+        if !locks.name {
+            self.name = rolled.name;
+        }
+        if !locks.reputation {
+            self.rep = rolled.rep;
+        }
+        if !locks.personality {
+            self.personality = rolled.personality;
+        }
+        if !locks.specialty {
+            self.refused
+                .retain(|category| !rolled.specialties.contains(category));
+            self.specialties = rolled.specialties;
+        }
+        if !locks.markups {
+            self.lowest_markup = rolled.lowest_markup;
+            self.avg_markup = rolled.avg_markup;
+            self.highest_markup = rolled.highest_markup;
+            self.buy_lowest = rolled.buy_lowest;
+            self.buy_avg = rolled.buy_avg;
+            self.buy_highest = rolled.buy_highest;
+        }
+        self.errors = EditErrors::default();
+    }
+
+    fn stance(&self, category: Category) -> Stance {
+        if self.refused.contains(&category) {
+            Stance::Refuses
+        } else if self.specialties.contains(&category) {
+            Stance::Specialty
+        } else {
+            Stance::Deals
+        }
+    }
+
+    fn set_stance(&mut self, category: Category, stance: Stance) {
+        self.specialties.retain(|known| *known != category);
+        self.refused.retain(|known| *known != category);
+        match stance {
+            Stance::Deals => {}
+            Stance::Specialty => self.specialties.push(category),
+            Stance::Refuses => self.refused.push(category),
+        }
+    }
+}
+
+fn delete_modal<'a>(fence: &Fence) -> Element<'a, FenceMessage> {
     container(
         column![
-            text("New Fence").size(24),
-            column![
-                column![
-                    text("Name").size(12),
-                    text_input("Merchant", &draft.name).on_input(FenceMessage::Name)
-                ],
-                column![
-                    text("reputation").size(12),
-                    text_input("enter a starting reputation", &draft.rep)
-                        .on_input(FenceMessage::Reputation),
-                ],
-                column![
-                    text("lowest").size(12),
-                    text_input("enter a lowest markup price", &draft.lowest_markup)
-                        .on_input(FenceMessage::Lowest),
-                    match &draft.errors.lowest {
-                        Some(err) => text(err.to_string()),
-                        None => text(""),
-                    }
-                ],
-                column![
-                    text("avg").size(12),
-                    text_input("enter an average markup price", &draft.avg_markup)
-                        .on_input(FenceMessage::Avg),
-                    match &draft.errors.average {
-                        Some(err) => text(err.to_string()),
-                        None => text(""),
-                    }
-                ],
-                column![
-                    text("highest").size(12),
-                    text_input("enter a highest markup price", &draft.highest_markup)
-                        .on_input(FenceMessage::Highest),
-                    match &draft.errors.highest {
-                        Some(err) => text(err.to_string()),
-                        None => text(""),
-                    }
-                ]
-                .spacing(5),
-                button(label).on_press(FenceMessage::Submit),
+            text(format!("Delete {}?", fence.name)).size(24),
+            text(format!(
+                "Its ledger of {} deals goes too. Undo brings both back.",
+                fence.ledger.transactions.len()
+            )),
+            row![
+                button("Delete").on_press(FenceMessage::ConfirmDelete),
+                button("Cancel").on_press(FenceMessage::HideModal),
             ]
             .spacing(10),
         ]
@@ -281,3 +1347,174 @@ fn fence_modal<'a>(draft: &EditDraft, is_editing: bool) -> Element<'a, FenceMess
     .style(container::rounded_box)
     .into()
 }
+
+fn fence_modal<'a>(
+    draft: &EditDraft,
+    regions: &Regions,
+    generator: Option<&'a GeneratorPanel>,
+    is_editing: bool,
+) -> Element<'a, FenceMessage> {
+    let label = if is_editing { "Save" } else { "Create" };
+    let locations: Vec<LocationChoice> = std::iter::once(LocationChoice(None))
+        .chain(
+            regions
+                .locations()
+                .into_iter()
+                .map(Some)
+                .map(LocationChoice),
+        )
+        .collect();
+    container(scrollable(
+        column![text("New Fence").size(24),]
+            .push(generator.map(|panel| panel.view().map(FenceMessage::Generate)))
+            .push(
+                column![
+                    column![
+                        text("Name").size(12),
+                        text_input("Merchant", &draft.name).on_input(FenceMessage::Name),
+                        text(draft.errors.for_field(FenceField::Name)),
+                    ],
+                    column![
+                        text("works in").size(12),
+                        pick_list(
+                            locations,
+                            Some(LocationChoice(draft.location.clone())),
+                            FenceMessage::Location
+                        ),
+                    ],
+                    column![
+                        text("personality").size(12),
+                        pick_list(
+                            std::iter::once(PersonalityChoice(None))
+                                .chain(Personality::ALL.map(Some).map(PersonalityChoice))
+                                .collect::<Vec<_>>(),
+                            Some(PersonalityChoice(draft.personality)),
+                            FenceMessage::Personality
+                        ),
+                    ],
+                    column![text("goods").size(12)]
+                        .extend(Category::ALL.map(|category| {
+                            row![
+                                text(category.to_string()).width(100),
+                                pick_list(
+                                    &Stance::ALL[..],
+                                    Some(draft.stance(category)),
+                                    move |stance| FenceMessage::Stance(category, stance)
+                                ),
+                            ]
+                            .spacing(10)
+                            .into()
+                        }))
+                        .spacing(5),
+                    column![
+                        text("reputation").size(12),
+                        text_input("enter a starting reputation", &draft.rep)
+                            .on_input(FenceMessage::Reputation),
+                        text(draft.errors.for_field(FenceField::Reputation)),
+                    ],
+                    column![
+                        text("charges at least").size(12),
+                        text_input("+8%, 108% of value or x1.08", &draft.lowest_markup)
+                            .on_input(FenceMessage::Lowest),
+                        text(draft.errors.for_field(FenceField::Lowest(Side::Sell))),
+                    ],
+                    column![
+                        text("charges on average").size(12),
+                        text_input("+10%, 110% of value or x1.1", &draft.avg_markup)
+                            .on_input(FenceMessage::Avg),
+                        text(draft.errors.for_field(FenceField::Average(Side::Sell))),
+                    ],
+                    column![
+                        text("charges at most").size(12),
+                        text_input("+20%, 120% of value or x1.2", &draft.highest_markup)
+                            .on_input(FenceMessage::Highest),
+                        text(draft.errors.for_field(FenceField::Highest(Side::Sell))),
+                    ],
+                    column![
+                        text("pays at least").size(12),
+                        text_input("50% of value, -50% or x0.5", &draft.buy_lowest)
+                            .on_input(FenceMessage::BuyLowest),
+                        text(draft.errors.for_field(FenceField::Lowest(Side::Buy))),
+                    ],
+                    column![
+                        text("pays on average").size(12),
+                        text_input("60% of value, -40% or x0.6", &draft.buy_avg)
+                            .on_input(FenceMessage::BuyAvg),
+                        text(draft.errors.for_field(FenceField::Average(Side::Buy))),
+                    ],
+                    column![
+                        text("pays at most").size(12),
+                        text_input("70% of value, -30% or x0.7", &draft.buy_highest)
+                            .on_input(FenceMessage::BuyHighest),
+                        text(draft.errors.for_field(FenceField::Highest(Side::Buy))),
+                    ],
+                    column![
+                        text("reputation curve").size(12),
+                        text_input("linear 0.1", &draft.curve).on_input(FenceMessage::Curve),
+                        match &draft.errors.curve {
+                            Some(err) => text(err.to_string()),
+                            None => text(""),
+                        }
+                    ],
+                    column![
+                        text("surcharges heat over").size(12),
+                        text_input("20", &draft.heat_comfort).on_input(FenceMessage::HeatComfort),
+                        match &draft.errors.heat_comfort {
+                            Some(err) => text(err.to_string()),
+                            None => text(""),
+                        }
+                    ],
+                    column![
+                        text("refuses heat over").size(12),
+                        text_input("80", &draft.heat_limit).on_input(FenceMessage::HeatLimit),
+                        match &draft.errors.heat_limit {
+                            Some(err) => text(err.to_string()),
+                            None => text(""),
+                        }
+                    ],
+                    column![
+                        text("risk surcharge curve").size(12),
+                        text_input("linear 0.5", &draft.heat_risk).on_input(FenceMessage::HeatRisk),
+                        match &draft.errors.heat_risk {
+                            Some(err) => text(err.to_string()),
+                            None => text(""),
+                        }
+                    ],
+                    column![
+                        text("volume discounts").size(12),
+                        text_input("100gp:2, 500gp:5", &draft.volume_tiers)
+                            .on_input(FenceMessage::VolumeTiers),
+                        match &draft.errors.volume_tiers {
+                            Some(err) => text(err.to_string()),
+                            None => text(""),
+                        }
+                    ],
+                    column![
+                        text("pays out at most a week").size(12),
+                        text_input("blank for no limit", &draft.weekly_cash)
+                            .on_input(FenceMessage::WeeklyCash),
+                        match &draft.errors.weekly_cash {
+                            Some(err) => text(err.to_string()),
+                            None => text(""),
+                        }
+                    ],
+                    column![
+                        text("rounding").size(12),
+                        pick_list(
+                            &RoundingMode::ALL[..],
+                            Some(draft.rounding),
+                            FenceMessage::Rounding
+                        ),
+                    ]
+                    .spacing(5),
+                    button(label).on_press(FenceMessage::Submit),
+                ]
+                .spacing(10),
+            )
+            .spacing(20),
+    ))
+    .width(300)
+    .padding(10)
+    .style(container::rounded_box)
+    .into()
+}
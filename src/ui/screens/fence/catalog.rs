@@ -0,0 +1,243 @@
+use iced::Element;
+use iced::widget::{Column, button, column, pick_list, row, text, text_input};
+
+use crate::model::catalog::{Catalog, Category, Item, LootBag, Rarity};
+use crate::model::heat::{Heat, HeatError};
+use crate::model::money::Denominations;
+
+#[derive(Debug, Clone)]
+pub enum CatalogMessage {
+    Search(String),
+    AddToBag(usize),
+    RemoveFromBag(usize),
+    NewItem,
+    EditItem(usize),
+    DeleteItem(usize),
+    Name(String),
+    Category(Category),
+    BaseValue(String),
+    Weight(String),
+    Rarity(Rarity),
+    Heat(String),
+    SaveItem,
+    CancelItem,
+}
+
+struct ItemDraft {
+    index: Option<usize>,
+    name: String,
+    category: Category,
+    base_value: String,
+    weight: String,
+    rarity: Rarity,
+    heat: String,
+    error: Option<String>,
+}
+
+impl ItemDraft {
+    fn new(index: Option<usize>, item: Option<&Item>, coins: &Denominations) -> Self {
+        Self {
+            index,
+            name: item.map(|item| item.name.clone()).unwrap_or_default(),
+            category: item.map_or(Category::Curios, |item| item.category),
+            base_value: item
+                .map(|item| coins.format(item.base_value))
+                .unwrap_or_default(),
+            weight: item.map(|item| item.weight.to_string()).unwrap_or_default(),
+            rarity: item.map_or(Rarity::Common, |item| item.rarity),
+            heat: item.map(|item| item.heat.to_string()).unwrap_or_default(),
+            error: None,
+        }
+    }
+
+    fn to_item(&self, coins: &Denominations) -> Result<Item, String> {
+        if self.name.trim().is_empty() {
+            return Err("Item needs a name".into());
+        }
+        let base_value = coins
+            .parse(&self.base_value)
+            .map_err(|err| err.to_string())?;
+        let weight = Item::parse_weight(&self.weight).ok_or("Invalid weight")?;
+        let heat = match self.heat.trim() {
+            "" => Heat::default(),
+            heat => heat.parse().map_err(|err: HeatError| err.to_string())?,
+        };
+
+        Ok(Item {
+            heat,
+            ..Item::new(
+                self.name.trim(),
+                self.category,
+                base_value,
+                weight,
+                self.rarity,
+            )
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct CatalogPanel {
+    query: String,
+    draft: Option<ItemDraft>,
+}
+
+impl CatalogPanel {
+    /// Applies `message` to the catalog and loot bag, returning whether anything worth saving changed.
+    pub fn update(
+        &mut self,
+        message: CatalogMessage,
+        catalog: &mut Catalog,
+        loot: &mut LootBag,
+        coins: &Denominations,
+        day: u32,
+    ) -> bool {
+        match message {
+            CatalogMessage::Search(query) => self.query = query,
+            CatalogMessage::AddToBag(index) => {
+                if let Some(item) = catalog.items.get(index) {
+                    loot.add(item, day);
+                    return true;
+                }
+            }
+            CatalogMessage::RemoveFromBag(index) => {
+                loot.remove_one(index);
+                return true;
+            }
+            CatalogMessage::NewItem => self.draft = Some(ItemDraft::new(None, None, coins)),
+            CatalogMessage::EditItem(index) => {
+                self.draft = Some(ItemDraft::new(Some(index), catalog.items.get(index), coins));
+            }
+            CatalogMessage::DeleteItem(index) => {
+                if index < catalog.items.len() {
+                    catalog.items.remove(index);
+                    self.draft = None;
+                    return true;
+                }
+            }
+            CatalogMessage::Name(name) => self.with_draft(|draft| draft.name = name),
+            CatalogMessage::Category(category) => {
+                self.with_draft(|draft| draft.category = category)
+            }
+            CatalogMessage::BaseValue(value) => self.with_draft(|draft| draft.base_value = value),
+            CatalogMessage::Weight(weight) => self.with_draft(|draft| draft.weight = weight),
+            CatalogMessage::Rarity(rarity) => self.with_draft(|draft| draft.rarity = rarity),
+            CatalogMessage::Heat(heat) => self.with_draft(|draft| draft.heat = heat),
+            CatalogMessage::CancelItem => self.draft = None,
+            CatalogMessage::SaveItem => {
+                if let Some(draft) = &mut self.draft {
+                    match draft.to_item(coins) {
+                        Ok(item) => {
+                            match draft.index.and_then(|index| catalog.items.get_mut(index)) {
+                                Some(slot) => *slot = item,
+                                None => catalog.items.push(item),
+                            }
+                            self.draft = None;
+                            return true;
+                        }
+                        Err(error) => draft.error = Some(error),
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn with_draft(&mut self, edit: impl FnOnce(&mut ItemDraft)) {
+        if let Some(draft) = &mut self.draft {
+            edit(draft);
+        }
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        catalog: &'a Catalog,
+        loot: &'a LootBag,
+        coins: &Denominations,
+        day: u32,
+    ) -> Element<'a, CatalogMessage> {
+        let results = Column::with_children(catalog.search(&self.query).map(|(index, item)| {
+            row![
+                text(format!(
+                    "{} ({}, {}, {} lb, heat {}) - {}",
+                    item.name,
+                    item.category,
+                    item.rarity,
+                    item.weight,
+                    item.heat,
+                    coins.format(item.base_value)
+                )),
+                button("Add to bag").on_press(CatalogMessage::AddToBag(index)),
+                button("Edit").on_press(CatalogMessage::EditItem(index)),
+                button("Delete").on_press(CatalogMessage::DeleteItem(index)),
+            ]
+            .spacing(10)
+            .into()
+        }))
+        .spacing(5);
+
+        let bag = Column::with_children(loot.entries.iter().enumerate().map(|(index, entry)| {
+            row![
+                text(format!(
+                    "{} x{}, taken on day {}, heat {}",
+                    entry.item.name,
+                    entry.quantity,
+                    entry.acquired_on,
+                    entry.heat_on(day)
+                )),
+                button("-").on_press(CatalogMessage::RemoveFromBag(index)),
+            ]
+            .spacing(10)
+            .into()
+        }))
+        .spacing(5);
+
+        column![
+            text("Item catalog").size(20),
+            row![
+                text_input("search items", &self.query).on_input(CatalogMessage::Search),
+                button("New item").on_press(CatalogMessage::NewItem),
+            ]
+            .spacing(10),
+        ]
+        .push(self.draft.as_ref().map(item_editor))
+        .push(results)
+        .push(text(match loot.total_base_value() {
+            Ok(value) => format!(
+                "Loot bag: base value {}, {} lb",
+                coins.format(value),
+                loot.total_weight()
+            ),
+            Err(err) => format!("Loot bag: {err}, {} lb", loot.total_weight()),
+        }))
+        .push(bag)
+        .spacing(10)
+        .into()
+    }
+}
+
+fn item_editor(draft: &ItemDraft) -> Element<'_, CatalogMessage> {
+    column![
+        row![
+            text_input("name", &draft.name).on_input(CatalogMessage::Name),
+            pick_list(
+                &Category::ALL[..],
+                Some(draft.category),
+                CatalogMessage::Category
+            ),
+            text_input("base value", &draft.base_value).on_input(CatalogMessage::BaseValue),
+            text_input("weight", &draft.weight).on_input(CatalogMessage::Weight),
+            pick_list(&Rarity::ALL[..], Some(draft.rarity), CatalogMessage::Rarity),
+            text_input("heat 0-100", &draft.heat).on_input(CatalogMessage::Heat),
+        ]
+        .spacing(10),
+        row![
+            button("Save item").on_press(CatalogMessage::SaveItem),
+            button("Cancel").on_press(CatalogMessage::CancelItem),
+        ]
+        .spacing(10),
+    ]
+    .push(draft.error.as_ref().map(text))
+    .spacing(5)
+    .into()
+}
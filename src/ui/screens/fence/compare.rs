@@ -0,0 +1,237 @@
+use std::fmt;
+
+use iced::Element;
+use iced::widget::{Column, checkbox, column, pick_list, row, text};
+
+use crate::model::catalog::{Catalog, Category, Item, LootBag, Rarity};
+use crate::model::fence::compare::{Split, best_split, rank_fences};
+use crate::model::fence::{Fence, LootQuote, PriceBand, Side, Tenths};
+use crate::model::money::{Denominations, Money};
+use crate::model::region::Regions;
+
+#[derive(Debug, Clone)]
+pub enum CompareMessage {
+    Source(SourceOption),
+    Side(Side),
+    Split(bool),
+}
+
+/// What is being compared across the fences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    LootBag,
+    BasePrice,
+    Item(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceOption {
+    source: Source,
+    label: String,
+}
+
+impl fmt::Display for SourceOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// Everything on the Fence screen the comparison reads.
+pub struct CompareInputs<'a> {
+    pub fences: &'a [Fence],
+    pub regions: &'a Regions,
+    pub catalog: &'a Catalog,
+    pub loot: &'a LootBag,
+    pub base_price: Option<Money>,
+    pub day: u32,
+    pub coins: &'a Denominations,
+}
+
+pub struct ComparePanel {
+    source: Source,
+    side: Side,
+    split: bool,
+}
+
+impl Default for ComparePanel {
+    fn default() -> Self {
+        Self {
+            source: Source::LootBag,
+            side: Side::Buy,
+            split: false,
+        }
+    }
+}
+
+impl ComparePanel {
+    pub fn update(&mut self, message: CompareMessage) {
+        match message {
+            CompareMessage::Source(option) => self.source = option.source,
+            CompareMessage::Side(side) => self.side = side,
+            CompareMessage::Split(split) => self.split = split,
+        }
+    }
+
+    fn goods(&self, inputs: &CompareInputs) -> Result<LootBag, String> {
+        let mut goods = LootBag::default();
+        match self.source {
+            Source::LootBag if inputs.loot.is_empty() => return Err("The loot bag is empty".into()),
+            Source::LootBag => return Ok(inputs.loot.clone()),
+            Source::BasePrice => {
+                let base_price = inputs.base_price.ok_or("Enter a valid base price")?;
+                let item = Item::new(
+                    "Item at base price",
+                    Category::Curios,
+                    base_price,
+                    0.0,
+                    Rarity::Common,
+                );
+                goods.add(&item, inputs.day);
+            }
+            Source::Item(index) => {
+                let item = inputs.catalog.items.get(index).ok_or("Pick an item")?;
+                goods.add(item, inputs.day);
+            }
+        }
+        Ok(goods)
+    }
+
+    pub fn view<'a>(&self, inputs: &CompareInputs) -> Element<'a, CompareMessage> {
+        let options: Vec<SourceOption> = [
+            SourceOption {
+                source: Source::LootBag,
+                label: "Loot bag".into(),
+            },
+            SourceOption {
+                source: Source::BasePrice,
+                label: "Base price".into(),
+            },
+        ]
+        .into_iter()
+        .chain(
+            inputs
+                .catalog
+                .items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| SourceOption {
+                    source: Source::Item(index),
+                    label: item.name.clone(),
+                }),
+        )
+        .collect();
+        let selected = options
+            .iter()
+            .find(|option| option.source == self.source)
+            .cloned();
+
+        let results: Element<'a, CompareMessage> = match self.results(inputs) {
+            Ok(results) => results,
+            Err(error) => text(error).into(),
+        };
+
+        column![
+            text("Best deal").size(20),
+            row![
+                pick_list(options, selected, CompareMessage::Source),
+                pick_list(&Side::ALL[..], Some(self.side), CompareMessage::Side),
+                checkbox(self.split)
+                    .label("Split across fences")
+                    .on_toggle(CompareMessage::Split),
+            ]
+            .spacing(10),
+            results,
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    fn results<'a>(&self, inputs: &CompareInputs) -> Result<Element<'a, CompareMessage>, String> {
+        let goods = self.goods(inputs)?;
+        let coins = inputs.coins;
+        let rankings = rank_fences(inputs.fences, inputs.regions, self.side, &goods, inputs.day)
+            .map_err(|err| err.to_string())?;
+
+        let mut lines = Column::new().spacing(4);
+        for (rank, ranking) in rankings.iter().enumerate() {
+            let name = &inputs.fences[ranking.index].name;
+            lines = lines.push(text(format!(
+                "{}. {name}: {} {}, {}",
+                rank + 1,
+                self.side,
+                coins.format(Money(ranking.quote.total.avg)),
+                self.cases(&ranking.quote.total, coins)
+            )));
+            for note in notes(&ranking.quote) {
+                lines = lines.push(text(format!("    {note}")));
+            }
+        }
+
+        if self.split {
+            let split = best_split(inputs.fences, inputs.regions, self.side, &goods, inputs.day)
+                .map_err(|err| err.to_string())?;
+            lines = lines.push(self.split_view(&split, &goods, inputs));
+        }
+        Ok(lines.into())
+    }
+
+    /// Best and worst case for the party, which depends on who is paying.
+    fn cases(&self, band: &PriceBand, coins: &Denominations) -> String {
+        let (worst, best) = match self.side {
+            Side::Buy => (band.low, band.high),
+            Side::Sell => (band.high, band.low),
+        };
+        format!(
+            "worst case {}, best case {}",
+            coins.format(Money(worst)),
+            coins.format(Money(best))
+        )
+    }
+
+    fn split_view<'a>(
+        &self,
+        split: &Split,
+        goods: &LootBag,
+        inputs: &CompareInputs,
+    ) -> Element<'a, CompareMessage> {
+        let coins = inputs.coins;
+        let assignments = split.assignments.iter().map(|assignment| {
+            text(format!(
+                "{} x{} to {}: {}",
+                assignment.line.name,
+                assignment.line.quantity,
+                inputs.fences[assignment.fence].name,
+                coins.format(Money(assignment.line.total.avg))
+            ))
+            .into()
+        });
+        let unplaced = split
+            .unplaced
+            .iter()
+            .map(|entry| text(format!("Nobody takes {}", goods.entries[*entry].item.name)).into());
+
+        column![text(format!(
+            "Split: {} {}, {}",
+            self.side,
+            coins.format(Money(split.total.avg)),
+            self.cases(&split.total, coins)
+        ))]
+        .extend(assignments.chain(unplaced))
+        .spacing(4)
+        .into()
+    }
+}
+
+/// Refusals and surcharges behind a fence's quote.
+fn notes(quote: &LootQuote) -> impl Iterator<Item = String> + '_ {
+    quote.lines.iter().filter_map(|line| match &line.refusal {
+        Some(refusal) => Some(format!("Refuses {}: {refusal}", line.name)),
+        None if line.surcharge > 0 => Some(format!(
+            "{}% risk surcharge on {} (heat {})",
+            Tenths(line.surcharge),
+            line.name,
+            line.heat
+        )),
+        None => None,
+    })
+}
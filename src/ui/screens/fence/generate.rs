@@ -0,0 +1,168 @@
+use iced::Element;
+use iced::widget::{button, checkbox, column, row, text, text_input};
+
+use crate::model::fence::Fence;
+use crate::model::fence::generate::{Locks, NameLists, Personality, reroll};
+
+#[derive(Debug, Clone)]
+pub enum GenerateMessage {
+    Seed(String),
+    /// Rolls the fence for the seed as typed.
+    RollSeed,
+    /// Rolls a fresh seed.
+    Reroll,
+    LockName(bool),
+    LockReputation(bool),
+    LockPersonality(bool),
+    LockSpecialty(bool),
+    LockMarkups(bool),
+    Names(String),
+    Epithets(String),
+}
+
+impl GenerateMessage {
+    pub fn edits_names(&self) -> bool {
+        matches!(
+            self,
+            GenerateMessage::Names(_) | GenerateMessage::Epithets(_)
+        )
+    }
+}
+
+/// Sits on top of the editor while it holds a generated fence.
+pub struct GeneratorPanel {
+    pub locks: Locks,
+    seed: String,
+    names: String,
+    epithets: String,
+    error: Option<String>,
+}
+
+impl GeneratorPanel {
+    pub fn new(seed: u64, names: &NameLists) -> Self {
+        Self {
+            locks: Locks::default(),
+            seed: seed.to_string(),
+            names: NameLists::format_list(&names.names),
+            epithets: NameLists::format_list(&names.epithets),
+            error: None,
+        }
+    }
+
+    /// Applies `message`, returning a freshly rolled fence when there is one. Locked fields
+    /// keep the editor's personality so the markups still suit it.
+    pub fn update(
+        &mut self,
+        message: GenerateMessage,
+        names: &mut NameLists,
+        personality: Option<Personality>,
+        taken_names: &[&str],
+    ) -> Option<Fence> {
+        let seed = match message {
+            GenerateMessage::Seed(seed) => {
+                self.seed = seed;
+                return None;
+            }
+            GenerateMessage::LockName(locked) => {
+                self.locks.name = locked;
+                return None;
+            }
+            GenerateMessage::LockReputation(locked) => {
+                self.locks.reputation = locked;
+                return None;
+            }
+            GenerateMessage::LockPersonality(locked) => {
+                self.locks.personality = locked;
+                return None;
+            }
+            GenerateMessage::LockSpecialty(locked) => {
+                self.locks.specialty = locked;
+                return None;
+            }
+            GenerateMessage::LockMarkups(locked) => {
+                self.locks.markups = locked;
+                return None;
+            }
+            GenerateMessage::Names(input) => {
+                names.names = NameLists::parse_list(&input);
+                self.names = input;
+                return None;
+            }
+            GenerateMessage::Epithets(input) => {
+                names.epithets = NameLists::parse_list(&input);
+                self.epithets = input;
+                return None;
+            }
+            GenerateMessage::RollSeed => match self.seed.trim().parse::<u64>() {
+                Ok(seed) => seed,
+                Err(_) => {
+                    self.error = Some("The seed must be a whole number".into());
+                    return None;
+                }
+            },
+            GenerateMessage::Reroll => {
+                let seed: u64 = rand::random();
+                self.seed = seed.to_string();
+                seed
+            }
+        };
+        self.error = None;
+        let previous = Fence {
+            personality,
+            ..Fence::default()
+        };
+        Some(reroll(&previous, self.locks, names, seed, taken_names))
+    }
+
+    pub fn view(&self) -> Element<'_, GenerateMessage> {
+        let Locks {
+            name,
+            reputation,
+            personality,
+            specialty,
+            markups,
+        } = self.locks;
+        column![
+            row![
+                text_input("seed", &self.seed).on_input(GenerateMessage::Seed),
+                button("Roll").on_press(GenerateMessage::RollSeed),
+                button("Re-roll").on_press(GenerateMessage::Reroll),
+            ]
+            .spacing(10),
+            text("Keep on re-roll").size(12),
+            row![
+                checkbox(name)
+                    .label("name")
+                    .on_toggle(GenerateMessage::LockName),
+                checkbox(reputation)
+                    .label("reputation")
+                    .on_toggle(GenerateMessage::LockReputation),
+                checkbox(personality)
+                    .label("personality")
+                    .on_toggle(GenerateMessage::LockPersonality),
+            ]
+            .spacing(10),
+            row![
+                checkbox(specialty)
+                    .label("specialty")
+                    .on_toggle(GenerateMessage::LockSpecialty),
+                checkbox(markups)
+                    .label("markups")
+                    .on_toggle(GenerateMessage::LockMarkups),
+            ]
+            .spacing(10),
+            column![
+                text("names").size(12),
+                text_input("Mags, Tobin, Vera", &self.names).on_input(GenerateMessage::Names),
+            ],
+            column![
+                text("epithets").size(12),
+                text_input("the Silent, Two-Coins", &self.epithets)
+                    .on_input(GenerateMessage::Epithets),
+            ],
+        ]
+        .push(self.error.as_ref().map(text))
+        .spacing(10)
+        .into()
+    }
+}
@@ -0,0 +1,165 @@
+use iced::Element;
+use iced::widget::{Column, button, column, row, text, text_input};
+
+use crate::model::fence::Fence;
+use crate::model::fence::haggle::{Haggle, HaggleOutcome, HaggleSetup, haggle};
+use crate::model::money::{Denominations, Money};
+use crate::model::region::Market;
+
+#[derive(Debug, Clone)]
+pub enum HaggleMessage {
+    Ask(String),
+    Skill(String),
+    Roll(String),
+    Seed(String),
+    Run,
+    /// Handled by the fence screen, which opens the ledger with the deal filled in.
+    Record,
+}
+
+pub struct HagglePanel {
+    pub index: usize,
+    ask: String,
+    skill: String,
+    roll: String,
+    seed: String,
+    result: Option<Haggle>,
+    error: Option<String>,
+}
+
+impl HagglePanel {
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            ask: String::new(),
+            skill: "0".into(),
+            roll: String::new(),
+            seed: String::new(),
+            result: None,
+            error: None,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        message: HaggleMessage,
+        fence: &Fence,
+        market: &Market,
+        base_price: Option<Money>,
+        coins: &Denominations,
+    ) {
+        match message {
+            HaggleMessage::Ask(ask) => self.ask = ask,
+            HaggleMessage::Skill(skill) => self.skill = skill,
+            HaggleMessage::Roll(roll) => self.roll = roll,
+            HaggleMessage::Seed(seed) => self.seed = seed,
+            HaggleMessage::Record => {}
+            HaggleMessage::Run => match self.setup(base_price, coins) {
+                Ok((setup, base_price)) => {
+                    self.seed = setup.seed.to_string();
+                    match haggle(fence, base_price.0, market, &setup) {
+                        Ok(result) => {
+                            self.result = Some(result);
+                            self.error = None;
+                        }
+                        Err(err) => {
+                            self.result = None;
+                            self.error = Some(err.to_string());
+                        }
+                    }
+                }
+                Err(error) => {
+                    self.result = None;
+                    self.error = Some(error);
+                }
+            },
+        }
+    }
+
+    /// The agreed price, if the last haggle ended in a deal.
+    pub fn deal(&self) -> Option<Money> {
+        match self.result.as_ref()?.outcome {
+            HaggleOutcome::Deal { price } => Some(Money(price)),
+            HaggleOutcome::WalkedAway => None,
+        }
+    }
+
+    fn setup(
+        &self,
+        base_price: Option<Money>,
+        coins: &Denominations,
+    ) -> Result<(HaggleSetup, Money), String> {
+        let base_price = base_price.ok_or("Enter a valid base price first")?;
+        let opening_ask = coins
+            .parse(&self.ask)
+            .map_err(|err| format!("Opening ask: {err}"))?
+            .0;
+        let skill_modifier = self
+            .skill
+            .trim()
+            .parse()
+            .map_err(|_| "Invalid skill modifier")?;
+        let roll = match self.roll.trim() {
+            "" => None,
+            roll => match roll.parse() {
+                Ok(roll @ 1..=20) => Some(roll),
+                _ => return Err("Roll must be between 1 and 20".into()),
+            },
+        };
+        let seed = match self.seed.trim() {
+            "" => rand::random(),
+            seed => seed.parse().map_err(|_| "Invalid seed")?,
+        };
+
+        Ok((
+            HaggleSetup {
+                opening_ask,
+                skill_modifier,
+                roll,
+                seed,
+            },
+            base_price,
+        ))
+    }
+
+    pub fn view(&self, coins: &Denominations) -> Element<'_, HaggleMessage> {
+        let log = self.result.as_ref().map(|result| {
+            let rounds = Column::with_children(result.rounds.iter().map(|round| {
+                text(format!(
+                    "Round {}: rolled {} ({}), party asks {}, fence offers {}",
+                    round.round,
+                    round.roll,
+                    round.total,
+                    coins.format(Money(round.party_ask)),
+                    coins.format(Money(round.fence_offer))
+                ))
+                .into()
+            }));
+            let outcome = match result.outcome {
+                HaggleOutcome::Deal { price } => format!("Deal at {}", coins.format(Money(price))),
+                HaggleOutcome::WalkedAway => "The fence walked away".into(),
+            };
+            column![rounds, text(outcome)]
+                .push(
+                    self.deal()
+                        .map(|_| button("Record in ledger").on_press(HaggleMessage::Record)),
+                )
+                .spacing(5)
+        });
+
+        column![
+            row![
+                text_input("opening ask", &self.ask).on_input(HaggleMessage::Ask),
+                text_input("skill modifier", &self.skill).on_input(HaggleMessage::Skill),
+                text_input("d20 roll (blank to roll)", &self.roll).on_input(HaggleMessage::Roll),
+                text_input("seed (blank for random)", &self.seed).on_input(HaggleMessage::Seed),
+                button("Haggle").on_press(HaggleMessage::Run),
+            ]
+            .spacing(10),
+        ]
+        .push(self.error.as_ref().map(text))
+        .push(log)
+        .spacing(10)
+        .into()
+    }
+}
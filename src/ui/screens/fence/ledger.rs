@@ -0,0 +1,250 @@
+use iced::Element;
+use iced::widget::{Column, button, column, pick_list, row, text, text_input};
+
+use crate::model::campaign::Calendar;
+use crate::model::catalog::{Catalog, Category, LootBag};
+use crate::model::fence::{Fence, PriceBand, Side};
+use crate::model::ledger::{Totals, TradedItem, Transaction};
+use crate::model::money::{Denominations, Money};
+use crate::model::region::Regions;
+
+#[derive(Debug, Clone)]
+pub enum LedgerMessage {
+    Character(String),
+    Side(Side),
+    Items(String),
+    Quoted(String),
+    Price(String),
+    Record,
+    Remove(usize),
+    /// Splits what the fence paid for a sale among the party. Handled by the screen.
+    Split(usize),
+}
+
+/// The deal currently on the table, used to fill in new ledger entries.
+pub struct DealContext<'a> {
+    pub loot: &'a LootBag,
+    pub catalog: &'a Catalog,
+    pub base_price: Option<Money>,
+    pub calendar: Calendar,
+    pub coins: &'a Denominations,
+    pub regions: &'a Regions,
+}
+
+impl DealContext<'_> {
+    /// The fence's prices for the loot bag, or for the base price when the bag is empty.
+    pub fn band(&self, fence: &Fence, side: Side) -> Option<PriceBand> {
+        let market = self.regions.market(fence.location.as_ref());
+        match self.loot.is_empty() {
+            false => Some(
+                fence
+                    .quote_loot(side, self.loot, self.calendar.day, &market)
+                    .ok()?
+                    .total,
+            ),
+            true => fence.price_band(side, self.base_price?.0, &market).ok(),
+        }
+    }
+
+    /// The side a new deal most likely falls on: selling loot if there is any, else buying.
+    pub fn likely_side(&self) -> Side {
+        match self.loot.is_empty() {
+            true => Side::Sell,
+            false => Side::Buy,
+        }
+    }
+
+    fn quote(&self, fence: &Fence, side: Side) -> Option<Money> {
+        self.band(fence, side).map(|band| Money(band.avg))
+    }
+
+    /// The category of goods called `name`, from the loot bag or else the catalog.
+    fn category_of(&self, name: &str) -> Option<Category> {
+        self.loot
+            .entries
+            .iter()
+            .find(|entry| entry.item.name.eq_ignore_ascii_case(name))
+            .map(|entry| entry.item.category)
+            .or_else(|| self.catalog.category_of(name))
+    }
+}
+
+pub struct LedgerPanel {
+    pub index: usize,
+    character: String,
+    side: Side,
+    items: String,
+    quoted: String,
+    price: String,
+    error: Option<String>,
+}
+
+impl LedgerPanel {
+    pub fn new(index: usize, fence: &Fence, deal: &DealContext) -> Self {
+        let side = deal.likely_side();
+        let quoted = deal
+            .quote(fence, side)
+            .map(|quote| deal.coins.format(quote))
+            .unwrap_or_default();
+        Self {
+            index,
+            character: String::new(),
+            side,
+            items: TradedItem::format_list(&TradedItem::from_loot(deal.loot)),
+            price: quoted.clone(),
+            quoted,
+            error: None,
+        }
+    }
+
+    /// Starts an entry for a haggle the party won, where the fence opened at `quoted`.
+    pub fn for_haggle(index: usize, quoted: Money, price: Money, coins: &Denominations) -> Self {
+        Self {
+            index,
+            character: String::new(),
+            side: Side::Sell,
+            items: String::new(),
+            quoted: coins.format(quoted),
+            price: coins.format(price),
+            error: None,
+        }
+    }
+
+    /// Applies `message` to the panel and the fence's ledger, returning whether the ledger changed.
+    pub fn update(
+        &mut self,
+        message: LedgerMessage,
+        fence: &mut Fence,
+        deal: &DealContext,
+    ) -> bool {
+        match message {
+            LedgerMessage::Character(character) => self.character = character,
+            LedgerMessage::Side(side) => {
+                self.side = side;
+                if let Some(quote) = deal.quote(fence, side) {
+                    self.quoted = deal.coins.format(quote);
+                }
+            }
+            LedgerMessage::Items(items) => self.items = items,
+            LedgerMessage::Quoted(quoted) => self.quoted = quoted,
+            LedgerMessage::Price(price) => self.price = price,
+            LedgerMessage::Remove(index) => {
+                fence.ledger.remove(index);
+                return true;
+            }
+            LedgerMessage::Split(_) => {}
+            LedgerMessage::Record => match self.transaction(deal) {
+                Ok(transaction) => {
+                    fence.ledger.record(transaction);
+                    self.error = None;
+                    return true;
+                }
+                Err(error) => self.error = Some(error),
+            },
+        }
+        false
+    }
+
+    fn transaction(&self, deal: &DealContext) -> Result<Transaction, String> {
+        let character = self.character.trim();
+        if character.is_empty() {
+            return Err("Who made the deal?".into());
+        }
+        let quoted = deal
+            .coins
+            .parse(&self.quoted)
+            .map_err(|err| format!("Quoted price: {err}"))?;
+        let price = deal
+            .coins
+            .parse(&self.price)
+            .map_err(|err| format!("Final price: {err}"))?;
+
+        Ok(Transaction {
+            day: deal.calendar.day,
+            session: deal.calendar.session,
+            character: character.to_string(),
+            side: self.side,
+            items: TradedItem::parse_list(&self.items)
+                .into_iter()
+                .map(|item| TradedItem {
+                    category: deal.category_of(&item.name),
+                    ..item
+                })
+                .collect(),
+            quoted,
+            price,
+        })
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        fence: &'a Fence,
+        coins: &Denominations,
+    ) -> Element<'a, LedgerMessage> {
+        let transactions = &fence.ledger.transactions;
+        let entries =
+            Column::with_children(transactions.iter().enumerate().map(|(index, transaction)| {
+                row![
+                    text(format!(
+                        "Day {} (session {}): {} - {} {}, quoted {}, {}",
+                        transaction.day,
+                        transaction.session,
+                        transaction.character,
+                        transaction.side,
+                        coins.format(transaction.price),
+                        coins.format(transaction.quoted),
+                        TradedItem::format_list(&transaction.items)
+                    )),
+                    button("Remove").on_press(LedgerMessage::Remove(index)),
+                ]
+                .push(
+                    (transaction.side == Side::Buy)
+                        .then(|| button("Split").on_press(LedgerMessage::Split(index))),
+                )
+                .spacing(10)
+                .into()
+            }))
+            .spacing(5);
+        let by_character = Totals::by(transactions, |t| t.character.clone())
+            .into_iter()
+            .map(|(character, totals)| text(format!("{character}: {}", describe(&totals, coins))));
+        let by_session =
+            Totals::by(transactions, |t| t.session)
+                .into_iter()
+                .map(|(session, totals)| {
+                    text(format!("Session {session}: {}", describe(&totals, coins)))
+                });
+
+        column![
+            text(format!(
+                "{}'s ledger: {}",
+                fence.name,
+                describe(&fence.ledger.totals(), coins)
+            )),
+            Column::with_children(by_character.chain(by_session).map(Element::from)).spacing(2),
+            entries,
+            row![
+                text_input("character", &self.character).on_input(LedgerMessage::Character),
+                pick_list(&Side::ALL[..], Some(self.side), LedgerMessage::Side),
+                text_input("items, i.e. Ruby x2, Gold ring", &self.items)
+                    .on_input(LedgerMessage::Items),
+                text_input("quoted price", &self.quoted).on_input(LedgerMessage::Quoted),
+                text_input("final price", &self.price).on_input(LedgerMessage::Price),
+                button("Record").on_press(LedgerMessage::Record),
+            ]
+            .spacing(10),
+        ]
+        .push(self.error.as_ref().map(text))
+        .spacing(10)
+        .into()
+    }
+}
+
+pub fn describe(totals: &Totals, coins: &Denominations) -> String {
+    format!(
+        "{} deals, paid {}, charged {}",
+        totals.deals,
+        coins.format(totals.paid),
+        coins.format(totals.charged)
+    )
+}
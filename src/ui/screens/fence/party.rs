@@ -0,0 +1,263 @@
+use iced::Element;
+use iced::widget::{Column, button, checkbox, column, row, text, text_input};
+
+use crate::model::campaign::Calendar;
+use crate::model::money::{Denominations, Money};
+use crate::model::party::{Fee, Member, Party, Payout, SplitRecord, parse_guild_cut};
+
+#[derive(Debug, Clone)]
+pub enum PartyMessage {
+    Name(String),
+    Shares(String),
+    AddMember,
+    RemoveMember(usize),
+    GuildCut(String),
+    Fees(String),
+    Source(String),
+    Amount(String),
+    /// Fills in the split from a sale or a quote elsewhere on the screen.
+    Propose {
+        source: String,
+        amount: Money,
+    },
+    Record,
+    Paid {
+        split: usize,
+        share: usize,
+        paid: bool,
+    },
+    RemoveSplit(usize),
+}
+
+#[derive(Default)]
+pub struct PartyPanel {
+    name: String,
+    shares: String,
+    guild_cut: String,
+    fees: String,
+    source: String,
+    amount: String,
+    error: Option<String>,
+}
+
+impl PartyPanel {
+    pub fn new(party: &Party, coins: &Denominations) -> Self {
+        Self {
+            guild_cut: match party.guild_cut {
+                0 => String::new(),
+                _ => party.describe_cut(),
+            },
+            fees: Fee::format_list(&party.fees, coins),
+            ..Self::default()
+        }
+    }
+
+    /// Applies `message` to the party, returning whether it changed.
+    pub fn update(
+        &mut self,
+        message: PartyMessage,
+        party: &mut Party,
+        calendar: Calendar,
+        coins: &Denominations,
+    ) -> bool {
+        match message {
+            PartyMessage::Name(name) => self.name = name,
+            PartyMessage::Shares(shares) => self.shares = shares,
+            PartyMessage::Source(source) => self.source = source,
+            PartyMessage::Amount(amount) => self.amount = amount,
+            PartyMessage::AddMember => {
+                let name = self.name.trim();
+                let shares = match self.shares.trim() {
+                    "" => Ok(1),
+                    shares => shares.parse::<u32>(),
+                };
+                if name.is_empty() {
+                    self.error = Some("Name the party member".into());
+                } else if party.members.iter().any(|member| member.name == name) {
+                    self.error = Some(format!("{name} is already in the party"));
+                } else if let Ok(shares) = shares {
+                    party.members.push(Member {
+                        name: name.to_string(),
+                        shares,
+                    });
+                    self.name.clear();
+                    self.shares.clear();
+                    self.error = None;
+                    return true;
+                } else {
+                    self.error = Some("Shares must be a whole number".into());
+                }
+            }
+            PartyMessage::RemoveMember(index) => {
+                if index < party.members.len() {
+                    party.members.remove(index);
+                    return true;
+                }
+            }
+            PartyMessage::GuildCut(cut) => {
+                let parsed = parse_guild_cut(&cut);
+                self.guild_cut = cut;
+                match parsed {
+                    Ok(cut) => {
+                        party.guild_cut = cut;
+                        self.error = None;
+                        return true;
+                    }
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            PartyMessage::Fees(fees) => {
+                let parsed = Fee::parse_list(&fees, coins);
+                self.fees = fees;
+                match parsed {
+                    Ok(fees) => {
+                        party.fees = fees;
+                        self.error = None;
+                        return true;
+                    }
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            PartyMessage::Propose { source, amount } => {
+                self.source = source;
+                self.amount = coins.format(amount);
+            }
+            PartyMessage::Record => match self.payout(party, coins) {
+                Ok(payout) => {
+                    party.record(SplitRecord {
+                        day: calendar.day,
+                        session: calendar.session,
+                        source: self.source.trim().to_string(),
+                        payout,
+                    });
+                    self.source.clear();
+                    self.amount.clear();
+                    self.error = None;
+                    return true;
+                }
+                Err(err) => self.error = Some(err),
+            },
+            PartyMessage::Paid { split, share, paid } => {
+                if let Some(share) = party
+                    .splits
+                    .get_mut(split)
+                    .and_then(|split| split.payout.shares.get_mut(share))
+                {
+                    share.paid = paid;
+                    return true;
+                }
+            }
+            PartyMessage::RemoveSplit(index) => {
+                if index < party.splits.len() {
+                    party.splits.remove(index);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    fn payout(&self, party: &Party, coins: &Denominations) -> Result<Payout, String> {
+        let amount = coins
+            .parse(&self.amount)
+            .map_err(|err| format!("Amount: {err}"))?;
+        party.split(amount).map_err(|err| err.to_string())
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        party: &'a Party,
+        coins: &Denominations,
+    ) -> Element<'a, PartyMessage> {
+        let members = party.members.iter().enumerate().map(|(index, member)| {
+            row![
+                text(format!("{} ({} shares)", member.name, member.shares)),
+                button("Remove").on_press(PartyMessage::RemoveMember(index)),
+            ]
+            .spacing(10)
+            .into()
+        });
+        let preview = (!self.amount.trim().is_empty()).then(|| match self.payout(party, coins) {
+            Ok(payout) => text(describe(&payout, coins)),
+            Err(err) => text(err),
+        });
+        let splits = party.splits.iter().enumerate().map(|(index, split)| {
+            let shares = split.payout.shares.iter().enumerate().map(|(share, cut)| {
+                checkbox(cut.paid)
+                    .label(format!("{}: {}", cut.name, coins.format(cut.amount)))
+                    .on_toggle(move |paid| PartyMessage::Paid {
+                        split: index,
+                        share,
+                        paid,
+                    })
+                    .into()
+            });
+            column![
+                row![
+                    text(format!(
+                        "Day {} (session {}): {} - {}",
+                        split.day,
+                        split.session,
+                        split.source,
+                        coins.format(split.payout.total)
+                    )),
+                    button("Remove").on_press(PartyMessage::RemoveSplit(index)),
+                ]
+                .spacing(10),
+                Column::with_children(shares).spacing(2),
+            ]
+            .spacing(5)
+            .into()
+        });
+        let owed = party
+            .owed()
+            .into_iter()
+            .map(|(name, owed)| text(format!("{name} is owed {}", coins.format(owed))).into());
+
+        column![
+            text("Party").size(20),
+            Column::with_children(members).spacing(5),
+            row![
+                text_input("name", &self.name).on_input(PartyMessage::Name),
+                text_input("shares (1)", &self.shares).on_input(PartyMessage::Shares),
+                button("Add member").on_press(PartyMessage::AddMember),
+            ]
+            .spacing(10),
+            row![
+                text_input("guild cut, i.e. 10%", &self.guild_cut).on_input(PartyMessage::GuildCut),
+                text_input("fees, i.e. Bribe: 5gp, Passage: 2gp", &self.fees)
+                    .on_input(PartyMessage::Fees),
+            ]
+            .spacing(10),
+            row![
+                text_input("what's being split, i.e. Sale to Dave", &self.source)
+                    .on_input(PartyMessage::Source),
+                text_input("amount", &self.amount).on_input(PartyMessage::Amount),
+                button("Record split").on_press(PartyMessage::Record),
+            ]
+            .spacing(10),
+        ]
+        .push(preview)
+        .push(self.error.as_ref().map(text))
+        .push(Column::with_children(splits).spacing(10))
+        .push(Column::with_children(owed).spacing(2))
+        .spacing(10)
+        .into()
+    }
+}
+
+fn describe(payout: &Payout, coins: &Denominations) -> String {
+    let fees = payout
+        .fees
+        .iter()
+        .map(|fee| format!("{} {}", fee.name, coins.format(fee.amount)));
+    let guild = (payout.guild > Money(0)).then(|| format!("guild {}", coins.format(payout.guild)));
+    let shares = payout
+        .shares
+        .iter()
+        .map(|share| format!("{} {}", share.name, coins.format(share.amount)));
+    fees.chain(guild)
+        .chain(shares)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
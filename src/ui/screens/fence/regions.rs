@@ -0,0 +1,157 @@
+use iced::Element;
+use iced::widget::{Column, button, column, row, text, text_input};
+
+use crate::model::fence::Fence;
+use crate::model::region::{PriceModifier, Regions};
+
+#[derive(Debug, Clone)]
+pub enum RegionMessage {
+    Region(String),
+    City(String),
+    Modifiers(String),
+    Save,
+    Edit {
+        region: String,
+        city: Option<String>,
+    },
+    Remove {
+        region: String,
+        city: Option<String>,
+    },
+}
+
+#[derive(Default)]
+pub struct RegionPanel {
+    region: String,
+    city: String,
+    modifiers: String,
+    error: Option<String>,
+}
+
+impl RegionPanel {
+    /// Applies `message` to the regions, returning whether they changed.
+    pub fn update(
+        &mut self,
+        message: RegionMessage,
+        regions: &mut Regions,
+        fences: &[Fence],
+    ) -> bool {
+        match message {
+            RegionMessage::Region(region) => self.region = region,
+            RegionMessage::City(city) => self.city = city,
+            RegionMessage::Modifiers(modifiers) => self.modifiers = modifiers,
+            RegionMessage::Edit { region, city } => {
+                let modifiers = regions.region(&region).and_then(|known| match &city {
+                    Some(city) => known
+                        .cities
+                        .iter()
+                        .find(|known| &known.name == city)
+                        .map(|city| &city.modifiers),
+                    None => Some(&known.modifiers),
+                });
+                self.modifiers = modifiers
+                    .map(|modifiers| PriceModifier::format_list(modifiers))
+                    .unwrap_or_default();
+                self.region = region;
+                self.city = city.unwrap_or_default();
+                self.error = None;
+            }
+            RegionMessage::Save => {
+                let region = self.region.trim();
+                let city = Some(self.city.trim()).filter(|city| !city.is_empty());
+                if region.is_empty() {
+                    self.error = Some("Name the region".into());
+                    return false;
+                }
+                match PriceModifier::parse_list(&self.modifiers) {
+                    Ok(modifiers) => {
+                        regions.set_modifiers(region, city, modifiers);
+                        *self = Self::default();
+                        return true;
+                    }
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+            }
+            RegionMessage::Remove { region, city } => {
+                let working = fences
+                    .iter()
+                    .filter_map(|fence| fence.location.as_ref())
+                    .filter(|location| {
+                        location.region == region
+                            && city.as_ref().is_none_or(|city| &location.city == city)
+                    })
+                    .count();
+                if working > 0 {
+                    self.error = Some(format!(
+                        "{working} fences still work in {}",
+                        city.as_ref().unwrap_or(&region)
+                    ));
+                    return false;
+                }
+                regions.remove(&region, city.as_deref());
+                self.error = None;
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn view<'a>(&'a self, regions: &'a Regions) -> Element<'a, RegionMessage> {
+        let places = regions.0.iter().flat_map(|region| {
+            let cities = region.cities.iter().map(|city| {
+                place(
+                    format!(
+                        "    {}: {}",
+                        city.name,
+                        PriceModifier::format_list(&city.modifiers)
+                    ),
+                    &region.name,
+                    Some(&city.name),
+                )
+            });
+            std::iter::once(place(
+                format!(
+                    "{}: {}",
+                    region.name,
+                    PriceModifier::format_list(&region.modifiers)
+                ),
+                &region.name,
+                None,
+            ))
+            .chain(cities)
+        });
+
+        column![
+            text("Regions").size(20),
+            Column::with_children(places).spacing(5),
+            row![
+                text_input("region", &self.region).on_input(RegionMessage::Region),
+                text_input("city (blank for the whole region)", &self.city)
+                    .on_input(RegionMessage::City),
+                text_input("Port discount -5%, Crackdown +10%", &self.modifiers)
+                    .on_input(RegionMessage::Modifiers),
+                button("Save").on_press(RegionMessage::Save),
+            ]
+            .spacing(10),
+        ]
+        .push(self.error.as_ref().map(text))
+        .spacing(10)
+        .into()
+    }
+}
+
+fn place<'a>(label: String, region: &str, city: Option<&String>) -> Element<'a, RegionMessage> {
+    row![
+        text(label),
+        button("Edit").on_press(RegionMessage::Edit {
+            region: region.to_string(),
+            city: city.cloned(),
+        }),
+        button("Remove").on_press(RegionMessage::Remove {
+            region: region.to_string(),
+            city: city.cloned(),
+        }),
+    ]
+    .spacing(10)
+    .into()
+}
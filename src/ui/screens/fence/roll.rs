@@ -0,0 +1,91 @@
+use iced::Element;
+use iced::widget::{button, column, pick_list, row, text, text_input};
+
+use crate::model::fence::roll::{PriceDistribution, roll_price};
+use crate::model::fence::{Fence, Side};
+use crate::model::money::{Denominations, Money};
+use crate::ui::screens::fence::ledger::DealContext;
+
+#[derive(Debug, Clone)]
+pub enum RollMessage {
+    Side(Side),
+    Distribution(PriceDistribution),
+    Seed(String),
+    Roll,
+}
+
+pub struct RollPanel {
+    pub index: usize,
+    side: Side,
+    distribution: PriceDistribution,
+    seed: String,
+    result: Option<Money>,
+    error: Option<String>,
+}
+
+impl RollPanel {
+    pub fn new(index: usize, deal: &DealContext) -> Self {
+        Self {
+            index,
+            side: deal.likely_side(),
+            distribution: PriceDistribution::default(),
+            seed: String::new(),
+            result: None,
+            error: None,
+        }
+    }
+
+    pub fn update(&mut self, message: RollMessage, fence: &Fence, deal: &DealContext) {
+        match message {
+            RollMessage::Side(side) => self.side = side,
+            RollMessage::Distribution(distribution) => self.distribution = distribution,
+            RollMessage::Seed(seed) => self.seed = seed,
+            RollMessage::Roll => {
+                let seed: u64 = match self.seed.trim() {
+                    "" => rand::random(),
+                    seed => match seed.parse() {
+                        Ok(seed) => seed,
+                        Err(_) => {
+                            self.error = Some("Invalid seed".into());
+                            return;
+                        }
+                    },
+                };
+                match deal.band(fence, self.side) {
+                    Some(band) => {
+                        self.seed = seed.to_string();
+                        self.result = Some(Money(roll_price(&band, self.distribution, seed)));
+                        self.error = None;
+                    }
+                    None => {
+                        self.result = None;
+                        self.error = Some("Enter a valid base price or fill the loot bag".into());
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn view(&self, coins: &Denominations) -> Element<'_, RollMessage> {
+        column![
+            row![
+                pick_list(&Side::ALL[..], Some(self.side), RollMessage::Side),
+                pick_list(
+                    &PriceDistribution::ALL[..],
+                    Some(self.distribution),
+                    RollMessage::Distribution
+                ),
+                text_input("seed (blank for random)", &self.seed).on_input(RollMessage::Seed),
+                button("Roll").on_press(RollMessage::Roll),
+            ]
+            .spacing(10),
+        ]
+        .push(
+            self.result
+                .map(|price| text(format!("{} {}", self.side, coins.format(price)))),
+        )
+        .push(self.error.as_ref().map(text))
+        .spacing(10)
+        .into()
+    }
+}